@@ -3,25 +3,211 @@ fn main() {
 
     println!(
         "Part 1: Sum of all the calibration values: {}",
-        part_one(&file_content)
+        part_one_bytes(&file_content)
     );
 
     println!(
         "Part 2: Sum of all the calibration values: {}",
         part_two_quick_and_dirty(&file_content)
     );
+
+    // Other puzzles use the same input with a different rule, e.g.
+    // `cargo run --bin day1 -- first-n=3`.
+    if let Some(rule) = std::env::args().nth(1) {
+        let rule: ExtractionRule = rule.parse().unwrap();
+        match calibration_sum(&file_content, rule) {
+            Ok(sum) => println!("Sum of all the calibration values with {rule:?}: {sum}"),
+            Err(overflow) => eprintln!("With {rule:?}: {overflow}"),
+        }
+    }
+}
+
+/// How the digits of a line are turned into a calibration value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ExtractionRule {
+    /// The puzzle rule: the first and last digits form a two-digit number.
+    #[default]
+    FirstAndLast,
+    /// All the digits of the line, in order, form the number.
+    AllDigits,
+    /// The first `n` digits form the number.
+    FirstN(usize),
+    /// The last `n` digits form the number.
+    LastN(usize),
+    /// The digits are summed instead of concatenated.
+    SumOfDigits,
+}
+
+impl ExtractionRule {
+    /// Computes the calibration value from the digits of a line. A line
+    /// without digits is worth 0. `None` if the value does not fit in a `u64`,
+    /// which can happen from 20 digits on.
+    fn apply(&self, digits: &[u64]) -> Option<u64> {
+        let selected = match *self {
+            ExtractionRule::FirstAndLast => match (digits.first(), digits.last()) {
+                (Some(&first), Some(&last)) => vec![first, last],
+                _ => Vec::new(),
+            },
+            ExtractionRule::AllDigits => digits.to_vec(),
+            ExtractionRule::FirstN(n) => digits.iter().take(n).copied().collect(),
+            ExtractionRule::LastN(n) => digits[digits.len().saturating_sub(n)..].to_vec(),
+            ExtractionRule::SumOfDigits => return Some(digits.iter().sum()),
+        };
+
+        selected
+            .into_iter()
+            .try_fold(0u64, |acc, digit| acc.checked_mul(10)?.checked_add(digit))
+    }
+}
+
+impl std::str::FromStr for ExtractionRule {
+    type Err = String;
+
+    /// Parses `first-and-last`, `all-digits`, `first-n=N`, `last-n=N` or
+    /// `sum-of-digits`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_n = |n: &str| n.parse().map_err(|_| format!("Invalid count: '{n}'"));
+
+        match s.split_once('=') {
+            None if s == "first-and-last" => Ok(ExtractionRule::FirstAndLast),
+            None if s == "all-digits" => Ok(ExtractionRule::AllDigits),
+            None if s == "sum-of-digits" => Ok(ExtractionRule::SumOfDigits),
+            Some(("first-n", n)) => Ok(ExtractionRule::FirstN(parse_n(n)?)),
+            Some(("last-n", n)) => Ok(ExtractionRule::LastN(parse_n(n)?)),
+            _ => Err(format!("Unknown extraction rule: '{s}'")),
+        }
+    }
 }
 
-fn part_one(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|line| {
-            let first_digit = line.chars().find(|c| c.is_ascii_digit()).unwrap();
-            let last_digit = line.chars().rfind(|c| c.is_ascii_digit()).unwrap();
-            let assembled = format!("{first_digit}{last_digit}");
-            assembled.parse::<u64>().unwrap()
-        })
-        .sum()
+/// The digits of a line, in order.
+fn digits(line: &str) -> Vec<u64> {
+    line.bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|b| (b - b'0') as u64)
+        .collect()
+}
+
+/// A calibration value, or the sum of the values up to it, that does not fit
+/// in a `u64`.
+#[derive(Debug, PartialEq, Eq)]
+enum Overflow {
+    Value { line: usize },
+    Sum { line: usize },
+}
+
+impl std::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Overflow::Value { line } => {
+                write!(f, "the calibration value of line {line} is too large")
+            }
+            Overflow::Sum { line } => write!(f, "the sum is too large at line {line}"),
+        }
+    }
+}
+
+/// Lines are numbered from 1 in the errors.
+fn calibration_sum(input: &str, rule: ExtractionRule) -> Result<u64, Overflow> {
+    input.lines().enumerate().try_fold(0u64, |sum, (i, line)| {
+        let value = rule
+            .apply(&digits(line))
+            .ok_or(Overflow::Value { line: i + 1 })?;
+        sum.checked_add(value).ok_or(Overflow::Sum { line: i + 1 })
+    })
+}
+
+const ONES: u64 = 0x0101_0101_0101_0101;
+const HIGHS: u64 = 0x8080_8080_8080_8080;
+
+/// Part one, working on raw bytes instead of `chars()`.
+///
+/// The input is read 8 bytes at a time as a `u64`. As long as a word is pure
+/// ASCII and has no newline, we can find its first and last digit with a few
+/// bit tricks instead of looking at each byte. Words that contain a newline or
+/// non-ASCII bytes fall back to the byte-per-byte path.
+fn part_one_bytes(input: &str) -> u64 {
+    let mut line = LineDigits::default();
+    let mut sum = 0;
+
+    let mut chunks = input.as_bytes().chunks_exact(8);
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+
+        if word & HIGHS == 0 && !has_byte(word, b'\n') {
+            let digits = digit_mask(word);
+            if digits != 0 {
+                // The mask keeps the high bit of each digit byte, so bit 8i+7
+                // is set when byte i is a digit (little-endian).
+                let first = (digits.trailing_zeros() / 8) as usize;
+                let last = 7 - (digits.leading_zeros() / 8) as usize;
+                line.push(chunk[first]);
+                line.push(chunk[last]);
+            }
+        } else {
+            for &byte in chunk {
+                sum += line.feed(byte);
+            }
+        }
+    }
+
+    for &byte in chunks.remainder() {
+        sum += line.feed(byte);
+    }
+
+    sum + line.finish()
+}
+
+/// First and last digit seen on the line being scanned.
+#[derive(Default)]
+struct LineDigits {
+    first: Option<u8>,
+    last: u8,
+}
+
+impl LineDigits {
+    fn push(&mut self, digit: u8) {
+        self.first.get_or_insert(digit);
+        self.last = digit;
+    }
+
+    /// Handles one byte. Returns the calibration value when it ends a line.
+    fn feed(&mut self, byte: u8) -> u64 {
+        match byte {
+            b'0'..=b'9' => {
+                self.push(byte);
+                0
+            }
+            b'\n' => self.finish(),
+            _ => 0,
+        }
+    }
+
+    fn finish(&mut self) -> u64 {
+        let value = match self.first {
+            Some(first) => ((first - b'0') * 10 + (self.last - b'0')) as u64,
+            None => 0,
+        };
+        *self = Self::default();
+        value
+    }
+}
+
+/// Sets the high bit of every byte of `word` that is an ASCII digit.
+///
+/// Only valid if all the bytes are ASCII: adding to a byte below 0x80 can then
+/// never carry into the next one.
+fn digit_mask(word: u64) -> u64 {
+    let at_least_zero = word.wrapping_add(ONES * (0x80 - b'0' as u64));
+    let above_nine = word.wrapping_add(ONES * (0x80 - b'9' as u64 - 1));
+    at_least_zero & !above_nine & HIGHS
+}
+
+/// Whether one of the bytes of `word` is `byte`. Same ASCII-only caveat.
+fn has_byte(word: u64, byte: u8) -> bool {
+    let x = word ^ (ONES * byte as u64);
+    // High bit is set for each non-zero byte of `x`.
+    let non_zero = ((x & !HIGHS) + !HIGHS) | x;
+    !non_zero & HIGHS != 0
 }
 
 /// In which the inner computer science guy in me dies.
@@ -40,20 +226,13 @@ fn part_two_quick_and_dirty(input: &str) -> u64 {
         .replace("eight", "e8t")
         .replace("nine", "n9e");
 
-    file_content
-        .lines()
-        .map(|line| {
-            let first_digit = line.chars().find(|c| c.is_ascii_digit()).unwrap();
-            let last_digit = line.chars().rfind(|c| c.is_ascii_digit()).unwrap();
-            let assembled = String::from_iter([first_digit, last_digit]);
-            assembled.parse::<u64>().unwrap()
-        })
-        .sum()
+    calibration_sum(&file_content, ExtractionRule::default()).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2023::random::XorShift64;
 
     #[test]
     fn example_part_1() {
@@ -65,6 +244,7 @@ mod tests {
         treb7uchet"#;
 
         assert_eq!(part_one(input), 142);
+        assert_eq!(part_one_bytes(input), 142);
     }
 
     #[test]
@@ -85,6 +265,128 @@ mod tests {
     fn real_part_1() {
         let input = std::fs::read_to_string("inputs/day1").unwrap();
         assert_eq!(part_one(&input), 54159);
+        assert_eq!(part_one_bytes(&input), 54159);
+    }
+
+    #[test]
+    fn extraction_rules() {
+        let line = digits("a1b2c3d4e5f");
+        assert_eq!(ExtractionRule::FirstAndLast.apply(&line), Some(15));
+        assert_eq!(ExtractionRule::AllDigits.apply(&line), Some(12345));
+        assert_eq!(ExtractionRule::FirstN(3).apply(&line), Some(123));
+        assert_eq!(ExtractionRule::LastN(2).apply(&line), Some(45));
+        assert_eq!(ExtractionRule::LastN(10).apply(&line), Some(12345));
+        assert_eq!(ExtractionRule::SumOfDigits.apply(&line), Some(15));
+
+        // A single digit is both the first and the last one.
+        assert_eq!(
+            ExtractionRule::FirstAndLast.apply(&digits("treb7uchet")),
+            Some(77)
+        );
+        assert_eq!(
+            ExtractionRule::FirstAndLast.apply(&digits("no digits")),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn parse_extraction_rules() {
+        assert_eq!("first-and-last".parse(), Ok(ExtractionRule::FirstAndLast));
+        assert_eq!("first-n=3".parse(), Ok(ExtractionRule::FirstN(3)));
+        assert_eq!("last-n=2".parse(), Ok(ExtractionRule::LastN(2)));
+        assert!("first-n=x".parse::<ExtractionRule>().is_err());
+        assert!("middle".parse::<ExtractionRule>().is_err());
+    }
+
+    #[test]
+    fn long_lines_overflow() {
+        let checksum = "log 1234567890 1234567890 1234567890 end";
+        assert_eq!(ExtractionRule::AllDigits.apply(&digits(checksum)), None);
+        assert_eq!(
+            ExtractionRule::FirstN(19).apply(&digits(checksum)),
+            Some(1234567890123456789)
+        );
+
+        let input = format!("12\n{checksum}\n");
+        assert_eq!(
+            calibration_sum(&input, ExtractionRule::FirstN(25)),
+            Err(Overflow::Value { line: 2 })
+        );
+        assert_eq!(calibration_sum(&input, ExtractionRule::LastN(2)), Ok(102));
+
+        let input = "9999999999999999999\n9999999999999999999";
+        assert_eq!(
+            calibration_sum(input, ExtractionRule::AllDigits),
+            Err(Overflow::Sum { line: 2 })
+        );
+    }
+
+    /// Random lines of letters and digits (each line has at least one digit),
+    /// with the odd non-ASCII char to exercise the fallback path.
+    fn generate_input(seed: u64, lines: usize) -> String {
+        let mut rng = XorShift64::new(seed);
+
+        let mut input = String::new();
+        for _ in 0..lines {
+            let length = 1 + rng.next_u64() % 60;
+            let digit_at = rng.next_u64() % length;
+            for i in 0..length {
+                let r = rng.next_u64();
+                if i == digit_at || r.is_multiple_of(7) {
+                    input.push((b'0' + (r % 10) as u8) as char);
+                } else if r.is_multiple_of(97) {
+                    input.push('é');
+                } else {
+                    input.push((b'a' + (r % 26) as u8) as char);
+                }
+            }
+            input.push('\n');
+        }
+        input
+    }
+
+    /// `part_one` as it was before the word-at-a-time scan, to compare the
+    /// results and the timings.
+    fn part_one(input: &str) -> u64 {
+        input
+            .lines()
+            .map(|line| {
+                let first_digit = line.chars().find(|c| c.is_ascii_digit()).unwrap();
+                let last_digit = line.chars().rfind(|c| c.is_ascii_digit()).unwrap();
+                let assembled = format!("{first_digit}{last_digit}");
+                assembled.parse::<u64>().unwrap()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn bytes_matches_chars_on_generated_inputs() {
+        for seed in 1..50 {
+            let input = generate_input(seed, 200);
+            assert_eq!(part_one_bytes(&input), part_one(&input), "seed {seed}");
+        }
+
+        // No trailing newline.
+        let input = generate_input(42, 10);
+        let input = input.trim_end();
+        assert_eq!(part_one_bytes(input), part_one(input));
+    }
+
+    /// Run with `cargo test --release --bin day1 -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_part_one() {
+        let input = generate_input(7, 1_000_000);
+
+        let start = std::time::Instant::now();
+        let expected = part_one(&input);
+        println!("part_one:       {:?}", start.elapsed());
+
+        let start = std::time::Instant::now();
+        let result = part_one_bytes(&input);
+        println!("part_one_bytes: {:?}", start.elapsed());
+
+        assert_eq!(result, expected);
     }
 
     #[test]
@@ -0,0 +1,348 @@
+//! How to change the bag so that more games become possible?
+//!
+//! Both questions boil down to choosing a subset of games, and the bag needed
+//! by a subset is the per-color maximum of its games' minimum bags (as in
+//! `part_two`). The best bag only ever holds, for each color, as many cubes as
+//! some game needs (or none). So we try every such combination for all the
+//! colors but the last one, and the last color is then picked directly: the
+//! search is exact, and cheap for the few colors of a game log.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use super::{Bag, Game};
+
+/// The smallest bag that makes all the `games` possible.
+pub fn minimum_bag(games: &[&Game]) -> Bag {
+    let mut cubes: BTreeMap<String, usize> = BTreeMap::new();
+    for (color, count) in games.iter().flat_map(|game| game.minimum_bag().cubes) {
+        let max = cubes.entry(color).or_default();
+        *max = (*max).max(count);
+    }
+    Bag { cubes }
+}
+
+/// How many cubes of each color must be added to `bag` so that all the
+/// `games` become possible. Colors that need no extra cube are left out.
+pub fn required_increase(games: &[&Game], bag: &Bag) -> Bag {
+    let cubes = minimum_bag(games)
+        .cubes
+        .into_iter()
+        .filter_map(|(color, needed)| {
+            let missing = needed.saturating_sub(bag.get(&color).unwrap_or(0));
+            (missing > 0).then_some((color, missing))
+        })
+        .collect();
+    Bag { cubes }
+}
+
+/// The smallest increase (in total number of cubes) to `bag` that makes at
+/// least `percentage` % of the games possible. Returns the increase and the
+/// games that become possible.
+pub fn smallest_increase<'a>(
+    games: &'a [Game],
+    bag: &Bag,
+    percentage: f64,
+) -> (Bag, Vec<&'a Game>) {
+    let target = (games.len() as f64 * percentage / 100.0).ceil() as usize;
+    let needs = Needs::new(games);
+    let in_bag: Vec<usize> = needs
+        .colors
+        .iter()
+        .map(|color| bag.get(color).unwrap_or(0))
+        .collect();
+
+    // (increase, games kept, limits)
+    let mut best: Option<(usize, usize, Vec<usize>)> = None;
+    needs.for_each_limits(|limits, candidates| {
+        let Some(last) = needs.colors.len().checked_sub(1) else {
+            // No colors at all, every game is already possible.
+            best = Some((0, games.len(), Vec::new()));
+            return;
+        };
+        if candidates.len() < target {
+            return;
+        }
+
+        // The `target` candidates needing the fewest cubes of the last color.
+        let mut last_needs: Vec<usize> = candidates.iter().map(|&g| needs.of[g][last]).collect();
+        last_needs.sort_unstable();
+        let mut limits = limits.to_vec();
+        limits.push(last_needs[..target].last().copied().unwrap_or(0));
+
+        let limits: Vec<usize> = limits
+            .iter()
+            .zip(&in_bag)
+            .map(|(&l, &b)| l.max(b))
+            .collect();
+        let increase: usize = limits.iter().zip(&in_bag).map(|(l, b)| l - b).sum();
+        let kept = needs.within(&limits).len();
+        if best
+            .as_ref()
+            .is_none_or(|&(i, k, _)| (increase, Reverse(kept)) < (i, Reverse(k)))
+        {
+            best = Some((increase, kept, limits));
+        }
+    });
+
+    let kept: Vec<&Game> = match best {
+        Some((_, _, limits)) => needs
+            .within(&limits)
+            .into_iter()
+            .map(|g| &games[g])
+            .collect(),
+        None => games.iter().collect(),
+    };
+    (required_increase(&kept, bag), kept)
+}
+
+/// The largest set of games that a bag of at most `budget` cubes makes
+/// possible, along with that bag. On ties, the set needing the smallest bag.
+pub fn largest_set_within_budget(games: &[Game], budget: usize) -> (Bag, Vec<&Game>) {
+    let needs = Needs::new(games);
+
+    // (games kept, bag size, kept games)
+    let mut best: Option<(usize, usize, Vec<usize>)> = None;
+    needs.for_each_limits(|limits, candidates| {
+        let Some(last) = needs.colors.len().checked_sub(1) else {
+            best = Some((games.len(), 0, (0..games.len()).collect()));
+            return;
+        };
+        let Some(left) = budget.checked_sub(limits.iter().sum()) else {
+            return;
+        };
+
+        // All the candidates fitting in what is left of the budget.
+        let kept: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&g| needs.of[g][last] <= left)
+            .collect();
+        let size: usize = (0..needs.colors.len())
+            .map(|color| kept.iter().map(|&g| needs.of[g][color]).max().unwrap_or(0))
+            .sum();
+        if best
+            .as_ref()
+            .is_none_or(|&(k, s, _)| (Reverse(kept.len()), size) < (Reverse(k), s))
+        {
+            best = Some((kept.len(), size, kept));
+        }
+    });
+
+    let kept: Vec<&Game> = best
+        .map(|(_, _, kept)| kept.into_iter().map(|g| &games[g]).collect())
+        .unwrap_or_default();
+    (minimum_bag(&kept), kept)
+}
+
+/// How many cubes of each color each game needs.
+struct Needs {
+    colors: Vec<String>,
+    /// `of[game][color]`, in the order of `colors`.
+    of: Vec<Vec<usize>>,
+}
+
+impl Needs {
+    fn new(games: &[Game]) -> Self {
+        let bags: Vec<Bag> = games.iter().map(Game::minimum_bag).collect();
+        let colors: Vec<String> = minimum_bag(&games.iter().collect::<Vec<_>>())
+            .cubes
+            .into_keys()
+            .collect();
+        let of = bags
+            .iter()
+            .map(|bag| {
+                colors
+                    .iter()
+                    .map(|color| bag.get(color).unwrap_or(0))
+                    .collect()
+            })
+            .collect();
+
+        Needs { colors, of }
+    }
+
+    /// Indices of the games needing at most `limits` cubes of the first colors.
+    fn within(&self, limits: &[usize]) -> Vec<usize> {
+        (0..self.of.len())
+            .filter(|&g| {
+                self.of[g]
+                    .iter()
+                    .zip(limits)
+                    .all(|(need, limit)| need <= limit)
+            })
+            .collect()
+    }
+
+    /// Calls `visit` with every combination of limits for all the colors but
+    /// the last one, and the games within these limits. Each limit is 0 or
+    /// what some game needs. Without any color, `visit` is called once.
+    fn for_each_limits(&self, mut visit: impl FnMut(&[usize], Vec<usize>)) {
+        let Some(last) = self.colors.len().checked_sub(1) else {
+            visit(&[], (0..self.of.len()).collect());
+            return;
+        };
+
+        let options: Vec<Vec<usize>> = (0..last)
+            .map(|color| {
+                let mut values: Vec<usize> = self.of.iter().map(|need| need[color]).collect();
+                values.push(0);
+                values.sort_unstable();
+                values.dedup();
+                values
+            })
+            .collect();
+
+        // Odometer over the options of each color.
+        let mut choice = vec![0; last];
+        loop {
+            let limits: Vec<usize> = choice.iter().zip(&options).map(|(&i, o)| o[i]).collect();
+            visit(&limits, self.within(&limits));
+
+            let Some(color) = (0..last).rev().find(|&c| choice[c] + 1 < options[c].len()) else {
+                return;
+            };
+            choice[color] += 1;
+            choice[color + 1..].fill(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_games;
+    use super::*;
+    use crate::tests::EXAMPLE;
+    use aoc2023::random::XorShift64;
+
+    fn ids(games: &[&Game]) -> Vec<usize> {
+        games.iter().map(|game| game.id).collect()
+    }
+
+    fn size(bag: &Bag) -> usize {
+        bag.cubes.values().sum()
+    }
+
+    #[test]
+    fn increase_for_all_games() {
+        let games = parse_games(EXAMPLE).unwrap();
+        let (increase, kept) = smallest_increase(&games, &Bag::default(), 100.0);
+
+        assert_eq!(increase, "8 red, 1 blue".parse().unwrap());
+        assert_eq!(ids(&kept), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn increase_for_some_games() {
+        let games = parse_games(EXAMPLE).unwrap();
+
+        // Games 1, 2 and 5 are already possible.
+        let (increase, kept) = smallest_increase(&games, &Bag::default(), 60.0);
+        assert!(increase.cubes.is_empty());
+        assert_eq!(ids(&kept), vec![1, 2, 5]);
+
+        // Game 4 needs 2 more red cubes and 1 more blue cube.
+        let (increase, kept) = smallest_increase(&games, &Bag::default(), 80.0);
+        assert_eq!(increase, "2 red, 1 blue".parse().unwrap());
+        assert_eq!(ids(&kept), vec![1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn budget() {
+        let games = parse_games(EXAMPLE).unwrap();
+
+        let (bag, kept) = largest_set_within_budget(&games, 20);
+        assert_eq!(bag, "6 red, 3 green, 6 blue".parse().unwrap());
+        assert_eq!(ids(&kept), vec![1, 2, 5]);
+
+        let (bag, kept) = largest_set_within_budget(&games, 1000);
+        assert_eq!(bag, "20 red, 13 green, 15 blue".parse().unwrap());
+        assert_eq!(kept.len(), 5);
+
+        let (_, kept) = largest_set_within_budget(&games, 0);
+        assert!(kept.is_empty());
+    }
+
+    #[test]
+    fn budget_keeps_a_game_when_one_fits() {
+        // Dropping games one at a time by cost used to drop them all.
+        let games = parse_games(
+            "Game 1: 5 red, 4 green, 2 blue
+            Game 2: 4 red
+            Game 3: 5 red, 1 green, 5 blue
+            Game 4: 3 red, 3 green, 5 blue
+            Game 5: 1 red, 5 green, 4 blue
+            Game 6: 1 red, 3 green, 4 blue",
+        )
+        .unwrap();
+
+        let (bag, kept) = largest_set_within_budget(&games, 5);
+        assert_eq!(bag, "4 red".parse().unwrap());
+        assert_eq!(ids(&kept), vec![2]);
+    }
+
+    /// A few games of up to 3 draws over 4 colors.
+    fn generate_games(rng: &mut XorShift64) -> Vec<Game> {
+        let mut input = Vec::new();
+        for id in 1..=1 + rng.below(8) {
+            let mut draws = Vec::new();
+            for _ in 0..1 + rng.below(3) {
+                let mut cubes = Vec::new();
+                for color in ["red", "green", "blue", "yellow"] {
+                    if rng.below(2) == 0 {
+                        cubes.push(format!("{} {color}", 1 + rng.below(6)));
+                    }
+                }
+                if cubes.is_empty() {
+                    cubes.push("1 red".to_owned());
+                }
+                draws.push(cubes.join(", "));
+            }
+            input.push(format!("Game {id}: {}", draws.join("; ")));
+        }
+        parse_games(&input.join("\n")).unwrap()
+    }
+
+    /// Every subset of the games.
+    fn subsets(games: &[Game]) -> impl Iterator<Item = Vec<&Game>> {
+        (0..1usize << games.len()).map(move |mask| {
+            (0..games.len())
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| &games[i])
+                .collect()
+        })
+    }
+
+    #[test]
+    fn same_as_brute_force() {
+        let mut rng = XorShift64::new(34);
+        let bag: Bag = "2 red, 3 green, 1 blue".parse().unwrap();
+
+        for _ in 0..300 {
+            let games = generate_games(&mut rng);
+
+            let budget = rng.below(20);
+            let (found_bag, kept) = largest_set_within_budget(&games, budget);
+            let best = subsets(&games)
+                .filter(|subset| size(&minimum_bag(subset)) <= budget)
+                .map(|subset| subset.len())
+                .max()
+                .unwrap();
+            assert_eq!(kept.len(), best, "budget {budget} for {games:?}");
+            assert!(size(&found_bag) <= budget);
+            assert_eq!(found_bag, minimum_bag(&kept));
+
+            let percentage = rng.below(101) as f64;
+            let target = (games.len() as f64 * percentage / 100.0).ceil() as usize;
+            let (increase, kept) = smallest_increase(&games, &bag, percentage);
+            let best = subsets(&games)
+                .filter(|subset| subset.len() >= target)
+                .map(|subset| size(&required_increase(&subset, &bag)))
+                .min()
+                .unwrap();
+            assert_eq!(size(&increase), best, "{percentage}% of {games:?}");
+            assert!(kept.len() >= target);
+            assert_eq!(increase, required_increase(&kept, &bag));
+        }
+    }
+}
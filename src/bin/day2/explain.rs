@@ -0,0 +1,142 @@
+//! Why is a game (im)possible with a given bag?
+
+use super::{first_violation, json, Bag, Game, Strictness, UnknownColor, Violation};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub id: usize,
+    /// `None` if the game is possible.
+    pub violation: Option<Violation>,
+    pub minimum_bag: Bag,
+}
+
+pub fn explain(
+    games: &[Game],
+    bag: &Bag,
+    strictness: Strictness,
+) -> Result<Vec<Explanation>, UnknownColor> {
+    games
+        .iter()
+        .map(|game| {
+            Ok(Explanation {
+                id: game.id,
+                violation: first_violation(game, bag, strictness)?,
+                minimum_bag: game.minimum_bag(),
+            })
+        })
+        .collect()
+}
+
+/// One line per game.
+pub fn to_text(explanations: &[Explanation]) -> String {
+    let mut text = String::new();
+
+    for explanation in explanations {
+        let verdict = match &explanation.violation {
+            None => "possible".to_owned(),
+            Some(v) => format!(
+                "impossible, draw {} shows {} {} but the bag has {}",
+                v.draw, v.count, v.color, v.limit
+            ),
+        };
+        text.push_str(&format!(
+            "Game {}: {verdict}. Minimum bag: {}\n",
+            explanation.id, explanation.minimum_bag
+        ));
+    }
+
+    text
+}
+
+/// A JSON array with one object per game.
+pub fn to_json(explanations: &[Explanation]) -> String {
+    let objects: Vec<String> = explanations
+        .iter()
+        .map(|explanation| {
+            let violation = match &explanation.violation {
+                None => "null".to_owned(),
+                Some(v) => format!(
+                    r#"{{"draw": {}, "color": {}, "count": {}, "limit": {}}}"#,
+                    v.draw,
+                    json::string(&v.color),
+                    v.count,
+                    v.limit
+                ),
+            };
+            let minimum_bag: Vec<String> = explanation
+                .minimum_bag
+                .cubes
+                .iter()
+                .map(|(color, count)| format!("{}: {count}", json::string(color)))
+                .collect();
+
+            format!(
+                r#"  {{"id": {}, "possible": {}, "violation": {violation}, "minimum_bag": {{{}}}}}"#,
+                explanation.id,
+                explanation.violation.is_none(),
+                minimum_bag.join(", ")
+            )
+        })
+        .collect();
+
+    format!("[\n{}\n]", objects.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_games;
+    use super::*;
+
+    const EXAMPLE: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red"#;
+
+    #[test]
+    fn explain_example() {
+        let games = parse_games(EXAMPLE).unwrap();
+        let explanations = explain(&games, &Bag::default(), Strictness::Lenient).unwrap();
+
+        assert_eq!(explanations[0].violation, None);
+        assert_eq!(
+            explanations[2].violation,
+            Some(Violation {
+                draw: 2,
+                color: "blue".to_owned(),
+                count: 15,
+                limit: 14
+            })
+        );
+        assert_eq!(
+            explanations[1].minimum_bag,
+            "20 red, 13 green, 6 blue".parse().unwrap()
+        );
+
+        assert_eq!(
+            to_text(&explanations),
+            "\
+Game 1: possible. Minimum bag: 6 blue, 2 green, 4 red
+Game 3: impossible, draw 0 shows 20 red but the bag has 12. Minimum bag: 6 blue, 13 green, 20 red
+Game 4: impossible, draw 2 shows 15 blue but the bag has 14. Minimum bag: 15 blue, 3 green, 14 red
+"
+        );
+    }
+
+    #[test]
+    fn explain_json() {
+        let games = parse_games(EXAMPLE).unwrap();
+        let explanations = explain(&games[..2], &Bag::default(), Strictness::Lenient).unwrap();
+
+        assert_eq!(
+            to_json(&explanations),
+            r#"[
+  {"id": 1, "possible": true, "violation": null, "minimum_bag": {"blue": 6, "green": 2, "red": 4}},
+  {"id": 3, "possible": false, "violation": {"draw": 0, "color": "red", "count": 20, "limit": 12}, "minimum_bag": {"blue": 6, "green": 13, "red": 20}}
+]"#
+        );
+    }
+
+    #[test]
+    fn json_strings() {
+        assert_eq!(json::string(r#"dark "blue""#), r#""dark \"blue\"""#);
+    }
+}
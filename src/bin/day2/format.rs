@@ -0,0 +1,424 @@
+//! Reading and writing games as canonical text, CSV or JSON.
+//!
+//! The canonical text is the puzzle format with normalized spacing:
+//! `Game 1: 3 blue, 4 red; 1 red, 2 green`. The CSV has one row per cubes
+//! (`game,draw,color,count`, draws are numbered from 0) and the JSON is an
+//! array of `{"id": 1, "draws": [[{"count": 3, "color": "blue"}, ...], ...]}`.
+//!
+//! Games are checked when read, whatever the format, so that whatever is read
+//! can be written in any format and read back: a game needs at least one draw, a
+//! draw at least one cube, and a color can't be empty, start or end with
+//! spaces, or contain one of `,;:"` or a control character.
+
+use super::{json, parse_games, Cubes, Draw, Game};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" | "txt" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format: '{s}'")),
+        }
+    }
+}
+
+impl Format {
+    /// Guessed from the extension of `path`, text by default.
+    pub fn from_path(path: &str) -> Format {
+        path.rsplit_once('.')
+            .and_then(|(_, extension)| extension.parse().ok())
+            .unwrap_or(Format::Text)
+    }
+
+    pub fn write(self, games: &[Game]) -> Result<String, String> {
+        match self {
+            Format::Text => Ok(to_text(games)),
+            Format::Csv => to_csv(games),
+            Format::Json => Ok(to_json(games)),
+        }
+    }
+
+    pub fn read(self, input: &str) -> Result<Vec<Game>, String> {
+        match self {
+            Format::Text => parse_games(input),
+            Format::Csv => from_csv(input),
+            Format::Json => from_json(input),
+        }
+    }
+}
+
+pub fn to_text(games: &[Game]) -> String {
+    games
+        .iter()
+        .map(|game| {
+            let draws: Vec<String> = game
+                .draws
+                .iter()
+                .map(|draw| {
+                    let cubes: Vec<String> = draw
+                        .cubes
+                        .iter()
+                        .map(|cubes| format!("{} {}", cubes.count, cubes.color))
+                        .collect();
+                    cubes.join(", ")
+                })
+                .collect();
+            format!("Game {}: {}\n", game.id, draws.join("; "))
+        })
+        .collect()
+}
+
+/// Fails on two games in a row with the same ID: their rows would read back
+/// as a single game.
+pub fn to_csv(games: &[Game]) -> Result<String, String> {
+    if let Some(pair) = games.windows(2).find(|pair| pair[0].id == pair[1].id) {
+        return Err(format!(
+            "Game {} twice in a row can't be written as CSV",
+            pair[0].id
+        ));
+    }
+
+    let mut csv = String::from("game,draw,color,count\n");
+    for game in games {
+        for (index, draw) in game.draws.iter().enumerate() {
+            for cubes in &draw.cubes {
+                csv.push_str(&format!(
+                    "{},{index},{},{}\n",
+                    game.id, cubes.color, cubes.count
+                ));
+            }
+        }
+    }
+    Ok(csv)
+}
+
+/// Rows of the same game must be contiguous, and contiguous rows with the same
+/// ID are the same game, which is what `to_csv` does.
+pub fn from_csv(input: &str) -> Result<Vec<Game>, String> {
+    let mut games: Vec<Game> = Vec::new();
+    let mut lines = input.lines().map(str::trim).enumerate();
+
+    match lines.next() {
+        Some((_, "game,draw,color,count")) => (),
+        _ => return Err("Missing CSV header: 'game,draw,color,count'".to_owned()),
+    }
+
+    for (line_number, line) in lines.filter(|(_, line)| !line.is_empty()) {
+        let error = |message: &str| format!("Line {}: {message}: '{line}'", line_number + 1);
+
+        let fields: Vec<&str> = line.split(',').collect();
+        let [game, draw, color, count] = fields[..] else {
+            return Err(error("Expected 4 fields"));
+        };
+        let game: usize = game.parse().map_err(|_| error("Invalid game ID"))?;
+        let draw: usize = draw.parse().map_err(|_| error("Invalid draw index"))?;
+        let count: usize = count.parse().map_err(|_| error("Invalid count"))?;
+
+        if games.last().map(|g| g.id) != Some(game) {
+            games.push(Game {
+                id: game,
+                draws: Vec::new(),
+            });
+        }
+        let draws = &mut games.last_mut().unwrap().draws;
+
+        if draw == draws.len() {
+            draws.push(Draw { cubes: Vec::new() });
+        } else if draw + 1 != draws.len() {
+            return Err(error("Draws must be numbered in order"));
+        }
+        draws.last_mut().unwrap().cubes.push(Cubes {
+            count,
+            color: color.to_owned(),
+        });
+    }
+
+    games.iter().try_for_each(check)?;
+    Ok(games)
+}
+
+/// Whether `game` can be written in any format, see the module documentation.
+pub fn check(game: &Game) -> Result<(), String> {
+    if game.draws.is_empty() {
+        return Err(format!("Game {}: no draws", game.id));
+    }
+
+    for (index, draw) in game.draws.iter().enumerate() {
+        if draw.cubes.is_empty() {
+            return Err(format!("Game {}: draw {index} has no cubes", game.id));
+        }
+
+        for cubes in &draw.cubes {
+            let color = &cubes.color;
+            if color.is_empty()
+                || color.trim() != color
+                || color.contains([',', ';', ':', '"'])
+                || color.chars().any(char::is_control)
+            {
+                return Err(format!("Game {}: invalid color '{color}'", game.id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn to_json(games: &[Game]) -> String {
+    let games: Vec<String> = games
+        .iter()
+        .map(|game| {
+            let draws: Vec<String> = game
+                .draws
+                .iter()
+                .map(|draw| {
+                    let cubes: Vec<String> = draw
+                        .cubes
+                        .iter()
+                        .map(|cubes| {
+                            format!(
+                                r#"{{"count": {}, "color": {}}}"#,
+                                cubes.count,
+                                json::string(&cubes.color)
+                            )
+                        })
+                        .collect();
+                    format!("[{}]", cubes.join(", "))
+                })
+                .collect();
+            format!(
+                r#"  {{"id": {}, "draws": [{}]}}"#,
+                game.id,
+                draws.join(", ")
+            )
+        })
+        .collect();
+
+    format!("[\n{}\n]\n", games.join(",\n"))
+}
+
+pub fn from_json(input: &str) -> Result<Vec<Game>, String> {
+    let document = json::parse(input)?;
+    let array = |value: &json::Value, what: &str| {
+        value
+            .as_array()
+            .map(<[_]>::to_vec)
+            .ok_or_else(|| format!("Expected an array of {what}"))
+    };
+
+    array(&document, "games")?
+        .iter()
+        .map(|game| {
+            let id = game
+                .get("id")
+                .and_then(json::Value::as_usize)
+                .ok_or("Expected a game with an 'id'")?;
+            let draws = array(game.get("draws").unwrap_or(&json::Value::Null), "draws")?
+                .iter()
+                .map(|draw| {
+                    let cubes = array(draw, "cubes")?
+                        .iter()
+                        .map(|cubes| {
+                            let count = cubes.get("count").and_then(json::Value::as_usize);
+                            let color = cubes.get("color").and_then(json::Value::as_str);
+                            match (count, color) {
+                                (Some(count), Some(color)) => Ok(Cubes {
+                                    count,
+                                    color: color.to_owned(),
+                                }),
+                                _ => Err(format!("Game {id}: expected a 'count' and a 'color'")),
+                            }
+                        })
+                        .collect::<Result<_, String>>()?;
+                    Ok(Draw { cubes })
+                })
+                .collect::<Result<_, String>>()?;
+
+            let game = Game { id, draws };
+            check(&game)?;
+            Ok(game)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+
+    /// `EXAMPLE` with messy spacing on purpose.
+    const MESSY: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2:  1 blue,2 green ; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
+
+    #[test]
+    fn canonical_text() {
+        let games = parse_games(MESSY).unwrap();
+        let text = to_text(&games);
+
+        assert_eq!(
+            text.lines().nth(1),
+            Some("Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue")
+        );
+        assert_eq!(parse_games(&text).unwrap(), games);
+        assert_eq!(games, parse_games(EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn invalid_text() {
+        let games = Format::Text.read("\nGame 1: 3 blue\n   \nGame 2: 1 red\n");
+        assert_eq!(games.map(|games| games.len()), Ok(2));
+
+        let error = |input: &str| Format::Text.read(input).unwrap_err();
+        assert_eq!(
+            error("Game 1: 3 blue\nGame 2: 1 red;"),
+            "Line 2: Expected a count and a color: ''"
+        );
+        assert_eq!(
+            error("Game 1: 3 blue\n\nGame 3 1 red"),
+            "Line 3: Missing ':' in 'Game 3 1 red'"
+        );
+        assert_eq!(
+            error("Round 1: 3 blue"),
+            "Line 1: Invalid game prefix: 'Round 1'"
+        );
+        assert_eq!(error("Game one: 3 blue"), "Line 1: Invalid game ID: 'one'");
+        assert_eq!(
+            error("Game 1: three blue"),
+            "Line 1: Invalid count: 'three'"
+        );
+        assert_eq!(
+            error("Game 1: 3 blue, 2 a:b"),
+            "Line 1: Game 1: invalid color 'a:b'"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let games = parse_games(EXAMPLE).unwrap();
+        for format in [Format::Text, Format::Csv, Format::Json] {
+            assert_eq!(
+                format.read(&format.write(&games).unwrap()),
+                Ok(games.clone())
+            );
+        }
+
+        // Duplicate IDs are valid, see `check_ids`.
+        let games = parse_games("Game 2: 1 blue\nGame 1: 1 red\nGame 2: 2 blue").unwrap();
+        for format in [Format::Text, Format::Csv, Format::Json] {
+            assert_eq!(
+                format.read(&format.write(&games).unwrap()),
+                Ok(games.clone())
+            );
+        }
+
+        // But CSV can't tell these two games from `Game 2: 1 blue, 2 blue`.
+        let games = parse_games("Game 2: 1 blue\nGame 2: 2 blue").unwrap();
+        for format in [Format::Text, Format::Json] {
+            assert_eq!(
+                format.read(&format.write(&games).unwrap()),
+                Ok(games.clone())
+            );
+        }
+        assert!(Format::Csv.write(&games).is_err());
+    }
+
+    #[test]
+    fn real_input_round_trips() {
+        let games = parse_games(&std::fs::read_to_string("inputs/day2").unwrap()).unwrap();
+        for format in [Format::Text, Format::Csv, Format::Json] {
+            assert_eq!(
+                format.read(&format.write(&games).unwrap()),
+                Ok(games.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn csv() {
+        let games = parse_games("Game 7: 3 blue, 4 red; 2 green").unwrap();
+        assert_eq!(
+            to_csv(&games).unwrap(),
+            "game,draw,color,count\n7,0,blue,3\n7,0,red,4\n7,1,green,2\n"
+        );
+        assert!(from_csv("game,draw,color,count\n7,1,blue,3\n").is_err());
+        assert!(from_csv("7,0,blue,3\n").is_err());
+    }
+
+    #[test]
+    fn json() {
+        let games = parse_games("Game 7: 3 blue, 4 red; 2 green").unwrap();
+        assert_eq!(
+            to_json(&games),
+            r#"[
+  {"id": 7, "draws": [[{"count": 3, "color": "blue"}, {"count": 4, "color": "red"}], [{"count": 2, "color": "green"}]]}
+]
+"#
+        );
+
+        // Any valid JSON layout is accepted.
+        let parsed = from_json(
+            r#"[{"draws": [[{"color": "blue", "count": 3}, {"color": "red", "count": 4}],
+                           [{"color": "green", "count": 2}]],
+                 "id": 7}]"#,
+        );
+        assert_eq!(parsed, Ok(games));
+        assert!(from_json(r#"[{"id": 7, "draws": [[{"count": -1, "color": "red"}]]}]"#).is_err());
+        assert!(from_json(r#"[{"id": 7}"#).is_err());
+        assert!(from_json(r#"[{"id": 18446744073709551616, "draws": [[]]}]"#).is_err());
+        assert_eq!(
+            from_json(r#"[{"id": 7, "draws": [[{"count": 3e0, "color": "blue"}]]}]"#),
+            Ok(parse_games("Game 7: 3 blue").unwrap())
+        );
+    }
+
+    #[test]
+    fn big_counts_round_trip() {
+        for count in [9007199254740993, usize::MAX] {
+            let games = parse_games(&format!("Game {count}: {count} red")).unwrap();
+            for format in [Format::Text, Format::Csv, Format::Json] {
+                assert_eq!(
+                    format.read(&format.write(&games).unwrap()),
+                    Ok(games.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unwritable_games() {
+        assert!(from_json(r#"[{"id": 1, "draws": []}]"#).is_err());
+        assert!(
+            from_json(r#"[{"id": 1, "draws": [[{"count": 1, "color": "red"}], []]}]"#).is_err()
+        );
+        for color in ["", " red", "red, blue", "red;", "a\"b", "red\n"] {
+            let input = format!(
+                r#"[{{"id": 1, "draws": [[{{"count": 1, "color": {}}}]]}}]"#,
+                json::string(color)
+            );
+            assert!(from_json(&input).is_err(), "{color:?}");
+        }
+        assert!(from_csv("game,draw,color,count\n1,0,,3\n").is_err());
+
+        // Spaces inside a color are fine.
+        let games =
+            from_json(r#"[{"id": 1, "draws": [[{"count": 1, "color": "light blue"}]]}]"#).unwrap();
+        for format in [Format::Text, Format::Csv, Format::Json] {
+            assert_eq!(
+                format.read(&format.write(&games).unwrap()),
+                Ok(games.clone())
+            );
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Which bag most plausibly produced a game log?
+//!
+//! We model each draw as a handful of cubes picked *with replacement*: every
+//! cube of a draw is independently of color `c` with probability
+//! `bag[c] / bag_size`. A draw showing `k_c` cubes of each color (`n` cubes in
+//! total) then has a multinomial probability:
+//!
+//! ```text
+//! n! / (k_1! ... k_m!) * p_1^k_1 * ... * p_m^k_m
+//! ```
+//!
+//! With replacement, the absolute size of the bag cannot be inferred from the
+//! draws, only the proportions of each color. So the estimation needs a bag
+//! size, which defaults to the smallest bag that could have produced the draws
+//! without replacement (the `part_two` minimum).
+
+use std::collections::BTreeMap;
+
+use super::{Bag, Draw, Game};
+
+/// All the draws of all the games, to run the inference over a whole log.
+pub fn all_draws(games: &[Game]) -> Vec<Draw> {
+    games.iter().flat_map(|game| game.draws.clone()).collect()
+}
+
+/// Log-likelihood of observing `draws` from `bag`. It is `-inf` when a draw
+/// shows a color the bag doesn't contain, or when the bag is empty.
+pub fn log_likelihood(draws: &[Draw], bag: &Bag) -> f64 {
+    let bag_size: usize = bag.cubes.values().sum();
+    if bag_size == 0 {
+        return f64::NEG_INFINITY;
+    }
+
+    draws
+        .iter()
+        .map(|draw| {
+            let counts = color_counts(std::slice::from_ref(draw));
+            let drawn: usize = counts.values().sum();
+
+            counts
+                .iter()
+                .fold(ln_factorial(drawn), |acc, (color, &count)| {
+                    let p = bag.get(color).unwrap_or(0) as f64 / bag_size as f64;
+                    acc - ln_factorial(count) + count as f64 * p.ln()
+                })
+        })
+        .sum()
+}
+
+/// Maximum likelihood estimate of the bag contents, scaled to `bag_size`
+/// cubes (or the minimum bag size if `None`).
+///
+/// Every observed color gets at least one cube, the remaining cubes are shared
+/// proportionally to the number of times each color was seen (largest
+/// remainder rounding).
+pub fn estimate(draws: &[Draw], bag_size: Option<usize>) -> Bag {
+    let counts = color_counts(draws);
+    let observed: usize = counts.values().sum();
+    let bag_size = bag_size.unwrap_or_else(|| minimum_bag_size(draws));
+
+    let mut cubes: BTreeMap<String, usize> = counts.keys().map(|c| (c.clone(), 1)).collect();
+    let to_share = bag_size.saturating_sub(cubes.len());
+
+    let mut remainders = Vec::new();
+    for (color, &count) in &counts {
+        let share = to_share as f64 * count as f64 / observed as f64;
+        *cubes.get_mut(color).unwrap() += share.floor() as usize;
+        remainders.push((share.fract(), color));
+    }
+
+    let shared: usize = cubes.values().sum::<usize>() - cubes.len();
+    remainders.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (_, color) in remainders.into_iter().take(to_share - shared) {
+        *cubes.get_mut(color).unwrap() += 1;
+    }
+
+    Bag { cubes }
+}
+
+/// The candidates sorted from the most to the least plausible.
+pub fn rank<'a>(draws: &[Draw], candidates: &'a [Bag]) -> Vec<(&'a Bag, f64)> {
+    let mut ranked: Vec<(&Bag, f64)> = candidates
+        .iter()
+        .map(|bag| (bag, log_likelihood(draws, bag)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
+
+/// Total number of cubes seen for each color. Colors only shown as `0 purple`
+/// were never seen: they don't weigh in the likelihood (`p^0 = 1`, even when
+/// `p = 0`) and don't get a cube in the estimate.
+fn color_counts(draws: &[Draw]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for cubes in draws.iter().flat_map(|draw| &draw.cubes) {
+        *counts.entry(cubes.color.clone()).or_default() += cubes.count;
+    }
+    counts.retain(|_, count| *count > 0);
+    counts
+}
+
+/// Sum over the colors of the most cubes shown at once.
+fn minimum_bag_size(draws: &[Draw]) -> usize {
+    let mut maxima: BTreeMap<&str, usize> = BTreeMap::new();
+    for draw in draws {
+        for cubes in &draw.cubes {
+            let max = maxima.entry(&cubes.color).or_default();
+            *max = (*max).max(draw.count(&cubes.color));
+        }
+    }
+    maxima.values().sum()
+}
+
+fn ln_factorial(n: usize) -> f64 {
+    (2..=n).map(|i| (i as f64).ln()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_games;
+    use super::*;
+    use crate::tests::EXAMPLE;
+
+    #[test]
+    fn estimate_one_game() {
+        let games = parse_games(EXAMPLE).unwrap();
+
+        // 5 red, 4 green and 9 blue cubes were seen. The minimum bag has
+        // 4 red, 2 green and 6 blue cubes, so 12 cubes in total.
+        let bag = estimate(&games[0].draws, None);
+        assert_eq!(bag, "3 red, 3 green, 6 blue".parse().unwrap());
+        assert_eq!(bag.cubes.values().sum::<usize>(), 12);
+
+        let bag = estimate(&games[0].draws, Some(36));
+        assert_eq!(bag, "10 red, 8 green, 18 blue".parse().unwrap());
+    }
+
+    #[test]
+    fn likelihood() {
+        let games = parse_games(EXAMPLE).unwrap();
+        let draws = all_draws(&games);
+
+        // Same proportions, same likelihood.
+        let small: Bag = "1 red, 1 green, 1 blue".parse().unwrap();
+        let big: Bag = "5 red, 5 green, 5 blue".parse().unwrap();
+        let diff = log_likelihood(&draws, &small) - log_likelihood(&draws, &big);
+        assert!(diff.abs() < 1e-9);
+
+        let no_red: Bag = "13 green, 14 blue".parse().unwrap();
+        assert_eq!(log_likelihood(&draws, &no_red), f64::NEG_INFINITY);
+
+        // A single draw of a single cube.
+        let draws = parse_games("Game 1: 1 red").unwrap()[0].draws.clone();
+        let bag: Bag = "1 red, 3 blue".parse().unwrap();
+        assert!((log_likelihood(&draws, &bag) - 0.25f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn estimate_is_ranked_first() {
+        let games = parse_games(EXAMPLE).unwrap();
+        let draws = all_draws(&games);
+
+        let estimated = estimate(&draws, Some(39));
+        let candidates = vec![
+            Bag::default(),
+            "13 green, 14 blue".parse().unwrap(),
+            estimated.clone(),
+            "30 red, 5 green, 4 blue".parse().unwrap(),
+        ];
+
+        let ranked = rank(&draws, &candidates);
+        assert_eq!(ranked[0].0, &estimated);
+        assert_eq!(ranked[3].0, &candidates[1]);
+    }
+
+    #[test]
+    fn colors_never_seen() {
+        let draws = parse_games("Game 1: 2 red, 0 purple; 1 blue, 0 red").unwrap()[0]
+            .draws
+            .clone();
+
+        assert_eq!(estimate(&draws, None), "2 red, 1 blue".parse().unwrap());
+        let likelihood = log_likelihood(&draws, &Bag::default());
+        assert!(likelihood.is_finite());
+
+        let empty: Bag = "0 red".parse().unwrap();
+        assert_eq!(log_likelihood(&draws, &empty), f64::NEG_INFINITY);
+        let candidates = [empty, Bag::default()];
+        assert_eq!(rank(&draws, &candidates)[0].0, &candidates[1]);
+    }
+}
@@ -0,0 +1,214 @@
+//! Just enough JSON to read and write our games, we don't pull a dependency for that.
+
+/// `s` as a quoted JSON string.
+pub fn string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    /// A literal made of digits only, kept exact.
+    Integer(usize),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// Members are kept in the order of the document.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The number, if it is a non-negative integer. Other literals than
+    /// `Integer` ones, like `1e3`, only when they are exact.
+    pub fn as_usize(&self) -> Option<usize> {
+        const EXACT: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
+        match self {
+            Value::Integer(n) => Some(*n),
+            Value::Number(n) if (0.0..=EXACT).contains(n) && n.fract() == 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: input.char_indices().peekable(),
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+
+    match parser.chars.next() {
+        None => Ok(value),
+        Some((i, c)) => Err(format!("Unexpected '{c}' at {i}")),
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => Err(format!("Expected '{expected}' at {i}, found '{c}'")),
+            None => Err(format!("Expected '{expected}', found the end of the input")),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, String> {
+        for expected in word.chars() {
+            match self.chars.next() {
+                Some((_, c)) if c == expected => (),
+                _ => return Err(format!("Invalid literal, expected '{word}'")),
+            }
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+
+        match self.chars.peek().copied() {
+            None => Err("Unexpected end of the input".to_owned()),
+            Some((_, 'n')) => self.literal("null", Value::Null),
+            Some((_, 't')) => self.literal("true", Value::Bool(true)),
+            Some((_, 'f')) => self.literal("false", Value::Bool(false)),
+            Some((_, '"')) => self.string().map(Value::String),
+            Some((_, '[')) => {
+                self.chars.next();
+                let mut values = Vec::new();
+                if !self.end_of_list(']') {
+                    loop {
+                        values.push(self.value()?);
+                        if self.end_of_list(']') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Value::Array(values))
+            }
+            Some((_, '{')) => {
+                self.chars.next();
+                let mut members = Vec::new();
+                if !self.end_of_list('}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(':')?;
+                        members.push((key, self.value()?));
+                        if self.end_of_list('}') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Value::Object(members))
+            }
+            Some((i, c)) if c == '-' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some((_, c)) = self
+                    .chars
+                    .next_if(|(_, c)| c.is_ascii_digit() || "+-.eE".contains(*c))
+                {
+                    number.push(c);
+                }
+                if number.bytes().all(|b| b.is_ascii_digit()) {
+                    number
+                        .parse()
+                        .map(Value::Integer)
+                        .map_err(|_| format!("Number too big at {i}: '{number}'"))
+                } else {
+                    number
+                        .parse()
+                        .map(Value::Number)
+                        .map_err(|_| format!("Invalid number at {i}: '{number}'"))
+                }
+            }
+            Some((i, c)) => Err(format!("Unexpected '{c}' at {i}")),
+        }
+    }
+
+    /// Consumes `close` if it is the next non-whitespace char.
+    fn end_of_list(&mut self, close: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|(_, c)| *c == close).is_some()
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+
+        loop {
+            match self.chars.next() {
+                None => return Err("Unterminated string".to_owned()),
+                Some((_, '"')) => return Ok(s),
+                Some((i, '\\')) => match self.chars.next().map(|(_, c)| c) {
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('/') => s.push('/'),
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('u') => {
+                        let hex: String = (0..4)
+                            .filter_map(|_| self.chars.next())
+                            .map(|(_, c)| c)
+                            .collect();
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("Invalid escape at {i}: '\\u{hex}'"))?;
+                        s.push(c);
+                    }
+                    _ => return Err(format!("Invalid escape at {i}")),
+                },
+                Some((_, c)) => s.push(c),
+            }
+        }
+    }
+}
@@ -0,0 +1,807 @@
+use std::collections::BTreeMap;
+
+mod adjust;
+mod explain;
+mod format;
+mod inference;
+mod json;
+mod power;
+mod query;
+
+/// Usage: `cargo run --bin day2 -- [--input <path>] [--output text|csv|json]
+///     [--bag "12 red, 13 green"] [--bag-file <path>] [--strict] [--infer]
+///     [--query "max(red) > 12"] [--explain] [--json] [--adjust <percentage>] [--budget <cubes>]
+///     [--big]`
+///
+/// The input can be a text, CSV or JSON log (see `format.rs`), guessed from
+/// the extension. With `--output`, the games are only printed in that format.
+/// With `--bag` or `--bag-file`, part 1 is also computed with that bag, and
+/// part 2 over the colors of that bag. `--big` computes part 2 with big
+/// integers, for inputs where the sum doesn't fit in a `usize`.
+/// With `--infer`, the most plausible bag for the whole log is estimated and
+/// compared to the puzzle bag (and the `--bag` one).
+/// With `--query`, the games matching the query are listed, or the total is
+/// printed for a `total ... where ...` query (see `query.rs`).
+/// With `--explain`, each game is checked against the bag and the reason why
+/// it is impossible is printed, as JSON with `--json`.
+/// With `--adjust`, the smallest bag increase making that percentage of the
+/// games possible is computed. With `--budget`, the bag of at most that many
+/// cubes making the most games possible.
+fn main() {
+    let options = Options::from_args(std::env::args().skip(1));
+    let path = options.input.as_deref().unwrap_or("inputs/day2");
+    let games = match format::Format::from_path(path).read(&std::fs::read_to_string(path).unwrap())
+    {
+        Ok(games) => games,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(output) = options.output {
+        match output.write(&games) {
+            Ok(written) => print!("{written}"),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // The parts work on the puzzle format.
+    let file_content = format::to_text(&games);
+
+    for issue in check_ids(&games) {
+        eprintln!("Warning: {issue}");
+    }
+
+    println!("Part 1: Sum of valid Game IDs: {}", part_one(&file_content));
+    if options.big {
+        let sum = power::sum_powers_big(&games, &PUZZLE_COLORS);
+        println!("Part 2: Sum of game powers: {sum}");
+    } else {
+        match part_two(&file_content) {
+            Ok(sum) => println!("Part 2: Sum of game powers: {sum}"),
+            Err(err) => println!("Part 2: {err}, try --big"),
+        }
+    }
+
+    if let Some(bag) = &options.bag {
+        match possible_games_sum(&games, bag, options.strictness) {
+            Ok(sum) => println!("Sum of valid Game IDs with a bag of {bag}: {sum}"),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                std::process::exit(1);
+            }
+        }
+
+        let colors: Vec<&str> = bag.cubes.keys().map(String::as_str).collect();
+        let sum = if options.big {
+            power::sum_powers_big(&games, &colors).to_string()
+        } else {
+            power::sum_powers(&games, &colors).map_or_else(|err| err.to_string(), |s| s.to_string())
+        };
+        println!("Sum of game powers over {}: {sum}", colors.join(", "));
+    }
+
+    if options.explain {
+        let bag = options.bag.clone().unwrap_or_default();
+        let explanations = match explain::explain(&games, &bag, options.strictness) {
+            Ok(explanations) => explanations,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                std::process::exit(1);
+            }
+        };
+
+        if options.json {
+            println!("{}", explain::to_json(&explanations));
+        } else {
+            print!("{}", explain::to_text(&explanations));
+        }
+    }
+
+    if let Some(percentage) = options.adjust {
+        let bag = options.bag.clone().unwrap_or_default();
+        let (increase, kept) = adjust::smallest_increase(&games, &bag, percentage);
+        println!(
+            "Adding {increase} makes {} games possible ({percentage}%)",
+            kept.len()
+        );
+    }
+
+    if let Some(budget) = options.budget {
+        let (bag, kept) = adjust::largest_set_within_budget(&games, budget);
+        let ids: Vec<String> = kept.iter().map(|game| game.id.to_string()).collect();
+        println!(
+            "A bag of {bag} makes {} games possible: {}",
+            kept.len(),
+            ids.join(", ")
+        );
+    }
+
+    if let Some(query) = &options.query {
+        let query: query::Query = match query.parse() {
+            Ok(query) => query,
+            Err(err) => {
+                eprintln!("Invalid query: {err}");
+                std::process::exit(1);
+            }
+        };
+
+        if let query::Query::Filter(filter) = &query {
+            let matching = query::run(filter, &games);
+            let ids: Vec<String> = matching.iter().map(|game| game.id.to_string()).collect();
+            println!("Matching games: {}", ids.join(", "));
+            println!(
+                "  Sum of IDs: {}",
+                matching.iter().map(|game| game.id).sum::<usize>()
+            );
+            match power::sum_powers(matching, &PUZZLE_COLORS) {
+                Ok(sum) => println!("  Sum of powers: {sum}"),
+                Err(err) => println!("  Sum of powers: {err}"),
+            }
+        } else {
+            match query.total(&games) {
+                Ok(total) => println!("Total: {total}"),
+                Err(err) => println!("Total: {err}"),
+            }
+        }
+    }
+
+    if options.infer {
+        let draws = inference::all_draws(&games);
+        let estimated = inference::estimate(&draws, None);
+        println!("Most plausible bag: {estimated}");
+
+        let mut candidates = vec![estimated, Bag::default()];
+        candidates.extend(options.bag);
+        for (bag, log_likelihood) in inference::rank(&draws, &candidates) {
+            println!("  log-likelihood {log_likelihood:>12.2} for {bag}");
+        }
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    input: Option<String>,
+    output: Option<format::Format>,
+    bag: Option<Bag>,
+    strictness: Strictness,
+    infer: bool,
+    query: Option<String>,
+    explain: bool,
+    json: bool,
+    adjust: Option<f64>,
+    budget: Option<usize>,
+    big: bool,
+}
+
+impl Options {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => options.input = Some(args.next().expect("--input needs a path")),
+                "--output" => {
+                    let format = args.next().expect("--output needs a format");
+                    options.output = Some(format.parse().unwrap());
+                }
+                "--bag" => {
+                    let bag = args.next().expect("--bag needs a value");
+                    options.bag = Some(bag.parse().unwrap());
+                }
+                "--bag-file" => {
+                    let path = args.next().expect("--bag-file needs a path");
+                    options.bag = Some(std::fs::read_to_string(path).unwrap().parse().unwrap());
+                }
+                "--strict" => options.strictness = Strictness::Strict,
+                "--infer" => options.infer = true,
+                "--explain" => options.explain = true,
+                "--big" => options.big = true,
+                "--json" => {
+                    options.explain = true;
+                    options.json = true;
+                }
+                "--adjust" => {
+                    let percentage = args.next().expect("--adjust needs a percentage");
+                    options.adjust = Some(percentage.parse().unwrap());
+                }
+                "--budget" => {
+                    let budget = args.next().expect("--budget needs a number of cubes");
+                    options.budget = Some(budget.parse().unwrap());
+                }
+                "--query" => options.query = Some(args.next().expect("--query needs a value")),
+                _ => panic!("Unknown argument: '{arg}'"),
+            }
+        }
+
+        options
+    }
+}
+
+/// One line of the input: "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red"
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Game {
+    id: usize,
+    draws: Vec<Draw>,
+}
+
+/// A handful of cubes shown by the Elf: "3 green, 4 blue, 1 red"
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Draw {
+    cubes: Vec<Cubes>,
+}
+
+/// "4 blue"
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cubes {
+    count: usize,
+    color: String,
+}
+
+impl Game {
+    /// The smallest bag that makes the game possible.
+    fn minimum_bag(&self) -> Bag {
+        let mut cubes = BTreeMap::new();
+        for cubes_drawn in self.draws.iter().flat_map(|draw| &draw.cubes) {
+            cubes.insert(cubes_drawn.color.clone(), self.max(&cubes_drawn.color));
+        }
+        Bag { cubes }
+    }
+
+    /// The number of cubes of each of the `colors` in the smallest possible
+    /// bag, multiplied together. `None` on overflow.
+    fn power(&self, colors: &[&str]) -> Option<usize> {
+        colors.iter().try_fold(1usize, |power, color| {
+            power.checked_mul(self.checked_max(color)?)
+        })
+    }
+
+    /// The biggest number of cubes of `color` shown at once during the game.
+    /// Saturates like `Draw::count`.
+    fn max(&self, color: &str) -> usize {
+        self.draws
+            .iter()
+            .map(|draw| draw.count(color))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// `max`, but `None` if a draw shows more cubes than a `usize` can hold.
+    fn checked_max(&self, color: &str) -> Option<usize> {
+        self.draws
+            .iter()
+            .try_fold(0, |max, draw| Some(draw.checked_count(color)?.max(max)))
+    }
+}
+
+impl Draw {
+    /// A color can be listed several times in a draw, the counts add up. The
+    /// sum saturates at `usize::MAX`: it is always bigger than any bag.
+    fn count(&self, color: &str) -> usize {
+        self.checked_count(color).unwrap_or(usize::MAX)
+    }
+
+    fn checked_count(&self, color: &str) -> Option<usize> {
+        self.counts(color).try_fold(0usize, usize::checked_add)
+    }
+
+    /// Never overflows, unless a draw lists a color more than 2^64 times.
+    fn wide_count(&self, color: &str) -> u128 {
+        self.counts(color).map(|count| count as u128).sum()
+    }
+
+    fn counts<'a>(&'a self, color: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.cubes
+            .iter()
+            .filter(move |cubes| cubes.color == color)
+            .map(|cubes| cubes.count)
+    }
+}
+
+/// Blank lines are skipped. The games are checked like the CSV and JSON ones
+/// (see `format.rs`), and lines are numbered from 1 in the errors.
+fn parse_games(input: &str) -> Result<Vec<Game>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_game(line).map_err(|err| format!("Line {}: {err}", i + 1)))
+        .collect()
+}
+
+fn parse_game(line: &str) -> Result<Game, String> {
+    let (prefix, game_data) = line
+        .split_once(':')
+        .ok_or_else(|| format!("Missing ':' in '{}'", line.trim()))?;
+    let id = prefix
+        .trim()
+        .strip_prefix("Game ")
+        .ok_or_else(|| format!("Invalid game prefix: '{}'", prefix.trim()))?;
+    let id = id
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid game ID: '{}'", id.trim()))?;
+
+    let game = Game {
+        id,
+        draws: game_data
+            .split(';')
+            .map(parse_draw)
+            .collect::<Result<_, _>>()?,
+    };
+    format::check(&game)?;
+    Ok(game)
+}
+
+/// Something suspicious about the game IDs of a log. None of them prevents
+/// computing an answer, but they hint at a truncated or corrupted log.
+#[derive(Debug, PartialEq, Eq)]
+enum IdIssue {
+    /// The ID was already used by a previous game.
+    Duplicate(usize),
+    /// No game has these IDs, although games with a bigger ID exist.
+    Missing(std::ops::Range<usize>),
+    /// The game comes after a game with a bigger ID.
+    OutOfOrder { id: usize, previous: usize },
+}
+
+fn check_ids(games: &[Game]) -> Vec<IdIssue> {
+    let mut issues = Vec::new();
+    let mut seen = std::collections::BTreeSet::new();
+    let mut previous: Option<usize> = None;
+
+    for game in games {
+        if !seen.insert(game.id) {
+            issues.push(IdIssue::Duplicate(game.id));
+        } else if let Some(previous) = previous.filter(|&previous| previous > game.id) {
+            issues.push(IdIssue::OutOfOrder {
+                id: game.id,
+                previous,
+            });
+        }
+        previous = Some(game.id);
+    }
+
+    // One issue per gap, not per ID: a typo can make an ID huge.
+    let mut expected = 1;
+    for &id in &seen {
+        if id > expected {
+            issues.push(IdIssue::Missing(expected..id));
+        }
+        expected = expected.max(id.saturating_add(1));
+    }
+
+    issues
+}
+
+impl std::fmt::Display for IdIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdIssue::Duplicate(id) => write!(f, "game {id} appears several times"),
+            IdIssue::Missing(ids) if ids.len() == 1 => write!(f, "game {} is missing", ids.start),
+            IdIssue::Missing(ids) => {
+                write!(f, "games {} to {} are missing", ids.start, ids.end - 1)
+            }
+            IdIssue::OutOfOrder { id, previous } => {
+                write!(f, "game {id} comes after game {previous}")
+            }
+        }
+    }
+}
+
+fn parse_draw(draw: &str) -> Result<Draw, String> {
+    let cubes = draw
+        .split(',')
+        .map(|cube_draw| {
+            let cube_draw = cube_draw.trim();
+            let (count, color) = cube_draw
+                .split_once(' ')
+                .ok_or_else(|| format!("Expected a count and a color: '{cube_draw}'"))?;
+            Ok(Cubes {
+                count: count
+                    .parse()
+                    .map_err(|_| format!("Invalid count: '{count}'"))?,
+                color: color.trim().to_owned(),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(Draw { cubes })
+}
+
+/// How many cubes of each color the bag contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bag {
+    cubes: BTreeMap<String, usize>,
+}
+
+impl Default for Bag {
+    /// The bag of the puzzle: 12 red cubes, 13 green cubes, and 14 blue cubes.
+    fn default() -> Self {
+        "12 red, 13 green, 14 blue".parse().unwrap()
+    }
+}
+
+impl std::str::FromStr for Bag {
+    type Err = String;
+
+    /// Same format as a draw ("12 red, 13 green"). Newlines can be used
+    /// instead of commas, which is handy for config files.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cubes = BTreeMap::new();
+        for part in s
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            let (count, color) = part
+                .split_once(' ')
+                .ok_or_else(|| format!("Missing color in '{part}'"))?;
+            let count: usize = count
+                .parse()
+                .map_err(|_| format!("Invalid count: '{count}'"))?;
+            *cubes.entry(color.trim().to_owned()).or_default() += count;
+        }
+
+        if cubes.is_empty() {
+            return Err("A bag needs at least one color".to_owned());
+        }
+        Ok(Bag { cubes })
+    }
+}
+
+impl std::fmt::Display for Bag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cubes: Vec<String> = self
+            .cubes
+            .iter()
+            .map(|(color, count)| format!("{count} {color}"))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+impl Bag {
+    /// Number of cubes of `color` in the bag, `None` if the bag does not know
+    /// about this color at all.
+    fn get(&self, color: &str) -> Option<usize> {
+        self.cubes.get(color).copied()
+    }
+}
+
+/// What to do with colors that are not part of the bag.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Strictness {
+    /// The bag simply has no cube of that color, so the game is impossible.
+    #[default]
+    Lenient,
+    /// The input is considered invalid.
+    Strict,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct UnknownColor {
+    game: usize,
+    color: String,
+}
+
+impl std::fmt::Display for UnknownColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "game {} uses unknown color '{}'", self.game, self.color)
+    }
+}
+
+/// The first time a game shows more cubes than the bag contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Violation {
+    /// Index of the draw in the game, starting at 0.
+    draw: usize,
+    color: String,
+    count: usize,
+    limit: usize,
+}
+
+/// In `Strict` mode, every color of the game is checked against the bag before
+/// looking for a violation, so that an unknown color is always reported.
+fn first_violation(
+    game: &Game,
+    bag: &Bag,
+    strictness: Strictness,
+) -> Result<Option<Violation>, UnknownColor> {
+    let all_cubes = || game.draws.iter().flat_map(|draw| &draw.cubes);
+
+    if strictness == Strictness::Strict {
+        if let Some(cubes) = all_cubes().find(|cubes| bag.get(&cubes.color).is_none()) {
+            return Err(UnknownColor {
+                game: game.id,
+                color: cubes.color.clone(),
+            });
+        }
+    }
+
+    for (index, draw) in game.draws.iter().enumerate() {
+        for cubes in &draw.cubes {
+            // Lenient: the bag has no cube of an unknown color.
+            let limit = bag.get(&cubes.color).unwrap_or(0);
+            let count = draw.count(&cubes.color);
+            if count > limit {
+                // do not bother to check the other draws.
+                return Ok(Some(Violation {
+                    draw: index,
+                    color: cubes.color.clone(),
+                    count,
+                    limit,
+                }));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn is_game_possible(game: &Game, bag: &Bag, strictness: Strictness) -> Result<bool, UnknownColor> {
+    first_violation(game, bag, strictness).map(|violation| violation.is_none())
+}
+
+fn possible_games_sum(
+    games: &[Game],
+    bag: &Bag,
+    strictness: Strictness,
+) -> Result<usize, UnknownColor> {
+    let mut sum = 0;
+    for game in games {
+        if is_game_possible(game, bag, strictness)? {
+            sum += game.id;
+        }
+    }
+
+    Ok(sum)
+}
+
+/// `possible_games_sum` with the puzzle bag: a game showing a color that is
+/// not in the bag is impossible.
+const PART_ONE_QUERY: &str =
+    "total id where only(red, green, blue) and max(red) <= 12 and max(green) <= 13 and max(blue) <= 14";
+
+const PART_TWO_QUERY: &str = "total power(red, green, blue)";
+
+fn part_one(input: &str) -> usize {
+    let query: query::Query = PART_ONE_QUERY.parse().unwrap();
+    query.total(&parse_games(input).unwrap()).unwrap()
+}
+
+/// The colors of the puzzle, used to compute the powers.
+const PUZZLE_COLORS: [&str; 3] = ["red", "green", "blue"];
+
+/// Too big inputs are reported, see `power::sum_powers_big` for these.
+fn part_two(input: &str) -> Result<usize, power::Overflow> {
+    let query: query::Query = PART_TWO_QUERY.parse().unwrap();
+    query.total(&parse_games(input).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) const EXAMPLE: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+                                     Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+                                     Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+                                     Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+                                     Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
+
+    #[test]
+    fn example_part_1() {
+        // The Elf would first like to know which games would have been possible
+        // if the bag contained only 12 red cubes, 13 green cubes, and 14 blue
+        // cubes?
+
+        // In the example above, games 1, 2, and 5 would have been possible if
+        // the bag had been loaded with that configuration. However, game 3
+        // would have been impossible because at one point the Elf showed you 20
+        // red cubes at once; similarly, game 4 would also have been impossible
+        // because the Elf showed you 15 blue cubes at once. If you add up the
+        // IDs of the games that would have been possible, you get 8.
+
+        let input = EXAMPLE;
+
+        assert_eq!(part_one(input), 8);
+    }
+
+    /// `part_two` as a fold over the draws of each game, to check the powers
+    /// against.
+    fn part_two_functional(input: &str) -> usize {
+        parse_games(input)
+            .unwrap()
+            .iter()
+            .map(|game| {
+                let (r, g, b) = game.draws.iter().fold((0, 0, 0), |acc, draw| {
+                    (
+                        acc.0.max(draw.count("red")),
+                        acc.1.max(draw.count("green")),
+                        acc.2.max(draw.count("blue")),
+                    )
+                });
+                r * g * b
+            })
+            .sum()
+    }
+
+    #[test]
+    fn example_part_2() {
+        // The Elf would first like to know which games would have been possible
+        // if the bag contained only 12 red cubes, 13 green cubes, and 14 blue
+        // cubes?
+
+        // In the example above, games 1, 2, and 5 would have been possible if
+        // the bag had been loaded with that configuration. However, game 3
+        // would have been impossible because at one point the Elf showed you 20
+        // red cubes at once; similarly, game 4 would also have been impossible
+        // because the Elf showed you 15 blue cubes at once. If you add up the
+        // IDs of the games that would have been possible, you get 8.
+
+        let input = EXAMPLE;
+
+        assert_eq!(part_two(input), Ok(2286));
+        assert_eq!(part_two_functional(input), 2286)
+    }
+
+    #[test]
+    fn real_part_1() {
+        let input = std::fs::read_to_string("inputs/day2").unwrap();
+        assert_eq!(part_one(&input), 2727);
+
+        let games = parse_games(&input).unwrap();
+        let sum = possible_games_sum(&games, &Bag::default(), Strictness::Lenient);
+        assert_eq!(sum, Ok(2727));
+    }
+
+    #[test]
+    fn unknown_colors_are_impossible() {
+        assert_eq!(part_one("Game 1: 1 purple\nGame 2: 1 red, 0 purple"), 2);
+    }
+
+    #[test]
+    fn real_part_2() {
+        let input = std::fs::read_to_string("inputs/day2").unwrap();
+        assert_eq!(part_two(&input), Ok(56580));
+        assert_eq!(part_two_functional(&input), 56580);
+    }
+
+    #[test]
+    fn ids_come_from_the_input() {
+        // Game 3 and 4 are filtered out, game 5 comes first.
+        let input = r#"Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue"#;
+
+        assert_eq!(part_one(input), 8);
+        assert_eq!(
+            check_ids(&parse_games(input).unwrap()),
+            vec![
+                IdIssue::OutOfOrder { id: 1, previous: 5 },
+                IdIssue::Missing(3..5)
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_ids_are_ranges() {
+        let issues = check_ids(
+            &parse_games(
+                "Game 1: 3 blue
+Game 3000000: 1 red
+Game 3: 1 red",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            issues,
+            vec![
+                IdIssue::OutOfOrder {
+                    id: 3,
+                    previous: 3000000
+                },
+                IdIssue::Missing(2..3),
+                IdIssue::Missing(4..3000000)
+            ]
+        );
+        assert_eq!(issues[1].to_string(), "game 2 is missing");
+        assert_eq!(issues[2].to_string(), "games 4 to 2999999 are missing");
+    }
+
+    #[test]
+    fn duplicate_ids() {
+        let input = r#"Game 1: 3 blue
+        Game 2: 1 blue
+        Game 2: 2 blue"#;
+
+        assert_eq!(
+            check_ids(&parse_games(input).unwrap()),
+            vec![IdIssue::Duplicate(2)]
+        );
+    }
+
+    #[test]
+    fn real_input_ids() {
+        let input = std::fs::read_to_string("inputs/day2").unwrap();
+        assert_eq!(check_ids(&parse_games(&input).unwrap()), vec![]);
+    }
+
+    #[test]
+    fn custom_bag() {
+        let games = parse_games(EXAMPLE).unwrap();
+
+        let bag: Bag = "20 red\n13 green\n15 blue\n".parse().unwrap();
+        assert_eq!(bag.get("red"), Some(20));
+        assert_eq!(possible_games_sum(&games, &bag, Strictness::Strict), Ok(15));
+
+        let bag: Bag = "6 red, 3 green, 2 blue".parse().unwrap();
+        assert_eq!(possible_games_sum(&games, &bag, Strictness::Strict), Ok(5));
+
+        // All the games need blue cubes.
+        let bag: Bag = "20 red, 20 green".parse().unwrap();
+        assert_eq!(possible_games_sum(&games, &bag, Strictness::Lenient), Ok(0));
+        assert_eq!(
+            possible_games_sum(&games, &bag, Strictness::Strict),
+            Err(UnknownColor {
+                game: 1,
+                color: "blue".to_owned()
+            })
+        );
+
+        // The unknown color comes after a violation.
+        let games = parse_games("Game 1: 20 red; 1 purple").unwrap();
+        let bag: Bag = "12 red".parse().unwrap();
+        assert_eq!(possible_games_sum(&games, &bag, Strictness::Lenient), Ok(0));
+        assert_eq!(
+            possible_games_sum(&games, &bag, Strictness::Strict),
+            Err(UnknownColor {
+                game: 1,
+                color: "purple".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_bags() {
+        assert!("12".parse::<Bag>().is_err());
+        assert!("12 red, 13".parse::<Bag>().is_err());
+        assert!("twelve red".parse::<Bag>().is_err());
+        assert!("".parse::<Bag>().is_err());
+        assert!(" ,\n".parse::<Bag>().is_err());
+    }
+
+    #[test]
+    fn parse_game() {
+        let games = parse_games("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        let cubes = |count, color: &str| Cubes {
+            count,
+            color: color.to_owned(),
+        };
+
+        assert_eq!(
+            games,
+            vec![Game {
+                id: 1,
+                draws: vec![
+                    Draw {
+                        cubes: vec![cubes(3, "blue"), cubes(4, "red")]
+                    },
+                    Draw {
+                        cubes: vec![cubes(1, "red"), cubes(2, "green"), cubes(6, "blue")]
+                    },
+                    Draw {
+                        cubes: vec![cubes(2, "green")]
+                    },
+                ]
+            }]
+        );
+        assert_eq!(games[0].max("blue"), 6);
+        assert_eq!(games[0].max("purple"), 0);
+    }
+}
@@ -0,0 +1,231 @@
+//! Sum of powers that doesn't silently wrap on big or generated inputs.
+//!
+//! `sum_powers` reports an overflow of `usize`, `sum_powers_big` never
+//! overflows thanks to a (very) minimal big integer.
+
+use super::Game;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// The game whose power, or whose addition to the sum, overflowed.
+    pub game: usize,
+}
+
+impl std::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the sum overflows at game {}", self.game)
+    }
+}
+
+pub fn sum_powers<'a>(
+    games: impl IntoIterator<Item = &'a Game>,
+    colors: &[&str],
+) -> Result<usize, Overflow> {
+    games.into_iter().try_fold(0usize, |sum, game| {
+        game.power(colors)
+            .and_then(|power| sum.checked_add(power))
+            .ok_or(Overflow { game: game.id })
+    })
+}
+
+pub fn sum_powers_big<'a>(games: impl IntoIterator<Item = &'a Game>, colors: &[&str]) -> BigUint {
+    let mut sum = BigUint::from(0);
+    for game in games {
+        let mut power = BigUint::from(1);
+        for color in colors {
+            let max = game.draws.iter().map(|draw| draw.wide_count(color)).max();
+            power.mul_assign(&BigUint::from(max.unwrap_or(0)));
+        }
+        sum.add_assign(&power);
+    }
+    sum
+}
+
+/// Unsigned integer of any size. Only what we need to sum products.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first. No trailing zero limbs, so
+    /// that zero is an empty vector and equality is structural.
+    limbs: Vec<u32>,
+}
+
+impl From<u128> for BigUint {
+    fn from(mut n: u128) -> Self {
+        let mut big = BigUint { limbs: Vec::new() };
+        while n > 0 {
+            big.limbs.push(n as u32);
+            n >>= 32;
+        }
+        big
+    }
+}
+
+impl BigUint {
+    /// Schoolbook multiplication.
+    pub fn mul_assign(&mut self, other: &BigUint) {
+        let mut product = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let current = product[i + j] as u64 + a as u64 * b as u64 + carry;
+                product[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            product[i + other.limbs.len()] = carry as u32;
+        }
+        while product.last() == Some(&0) {
+            product.pop();
+        }
+        self.limbs = product;
+    }
+
+    pub fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0u64;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + *other.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Divides in place, returns the remainder.
+    fn div_rem_assign(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let current = (remainder << 32) | *limb as u64;
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        remainder as u32
+    }
+}
+
+impl std::fmt::Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Peel off 9 decimal digits at a time.
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.limbs.is_empty() {
+            chunks.push(n.div_rem_assign(1_000_000_000));
+        }
+
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((most_significant, rest)) => {
+                write!(f, "{most_significant}")?;
+                rest.iter()
+                    .rev()
+                    .try_for_each(|chunk| write!(f, "{chunk:09}"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_games, PUZZLE_COLORS};
+    use super::*;
+
+    #[test]
+    fn big_uint() {
+        assert_eq!(BigUint::from(0).to_string(), "0");
+        assert_eq!(
+            BigUint::from(usize::MAX as u128).to_string(),
+            usize::MAX.to_string()
+        );
+
+        let mut n = BigUint::from(usize::MAX as u128);
+        n.mul_assign(&BigUint::from(usize::MAX as u128));
+        n.add_assign(&BigUint::from(1_000_000_007));
+        let expected = usize::MAX as u128 * usize::MAX as u128 + 1_000_000_007;
+        assert_eq!(n.to_string(), expected.to_string());
+        assert_eq!(BigUint::from(expected), n);
+
+        // 2^64 * 2^64 doesn't fit in a u128.
+        let mut n = BigUint::from(1 << 64);
+        n.mul_assign(&BigUint::from(1 << 64));
+        let mut expected = BigUint::from(u128::MAX);
+        expected.add_assign(&BigUint::from(1));
+        assert_eq!(n, expected);
+        assert_eq!(n.to_string(), "340282366920938463463374607431768211456");
+
+        n.mul_assign(&BigUint::from(0));
+        assert_eq!(n, BigUint::from(0));
+    }
+
+    #[test]
+    fn more_colors() {
+        let games = parse_games(
+            "Game 1: 2 red, 3 green, 4 blue, 5 purple
+            Game 2: 1 red, 1 green; 1 blue",
+        )
+        .unwrap();
+
+        assert_eq!(sum_powers(&games, &PUZZLE_COLORS), Ok(25));
+        assert_eq!(
+            sum_powers(&games, &["red", "green", "blue", "purple"]),
+            Ok(120)
+        );
+        assert_eq!(
+            sum_powers_big(&games, &["red", "green", "blue", "purple"]),
+            BigUint::from(120)
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let big = usize::MAX / 2;
+        let games = parse_games(&format!(
+            "Game 1: {big} red, 1 green, 1 blue
+            Game 2: {big} red, 1 green, 1 blue
+            Game 3: {big} red, 1 green, 1 blue
+            Game 4: {big} red, 2 green, 2 blue"
+        ))
+        .unwrap();
+
+        assert_eq!(sum_powers(&games[..2], &PUZZLE_COLORS), Ok(usize::MAX - 1));
+        assert_eq!(
+            sum_powers(&games, &PUZZLE_COLORS),
+            Err(Overflow { game: 3 })
+        );
+        assert_eq!(
+            sum_powers(&games[3..], &PUZZLE_COLORS),
+            Err(Overflow { game: 4 })
+        );
+
+        let expected = big as u128 * 7;
+        assert_eq!(
+            sum_powers_big(&games, &PUZZLE_COLORS).to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn same_color_twice_in_a_draw() {
+        let games = parse_games(&format!(
+            "Game 1: {} red, 1 red, 1 green, 1 blue",
+            usize::MAX
+        ))
+        .unwrap();
+
+        assert_eq!(games[0].max("red"), usize::MAX);
+        assert_eq!(
+            sum_powers(&games, &PUZZLE_COLORS),
+            Err(Overflow { game: 1 })
+        );
+        assert_eq!(
+            sum_powers_big(&games, &PUZZLE_COLORS).to_string(),
+            (usize::MAX as u128 + 1).to_string()
+        );
+    }
+}
@@ -0,0 +1,530 @@
+//! A small expression language to filter games, e.g.
+//! `max(red) > 12 or any(draw, blue >= 10 and green == 0)`, and to sum a value
+//! over the matching games, e.g. `total id where max(red) <= 12`.
+//!
+//! ```text
+//! query      := "total" value ("where" expr)? | expr
+//! expr       := and ("or" and)*
+//! and        := unary ("and" unary)*
+//! unary      := "not" unary | "(" expr ")" | "true" | "false"
+//!             | ("any" | "all") "(" "draw" "," expr ")" | "only" "(" colors ")"
+//!             | comparison
+//! comparison := value ("<" | "<=" | ">" | ">=" | "==" | "!=") value
+//! value      := number | "id" | ("max" | "min" | "sum") "(" color ")"
+//!             | "power" "(" colors ")" | color
+//! colors     := color ("," color)*
+//! ```
+//!
+//! `max`, `min` and `sum` aggregate a color over all the draws of a game, and
+//! `power` multiplies the `max` of its colors. A bare color is the number of
+//! cubes of that color in the current draw, so it can only be used inside
+//! `any(draw, ...)` or `all(draw, ...)`. `only(red, green)` is true when the
+//! game shows no cube of any other color. Without `where`, a total is over all
+//! the games.
+
+use super::power::Overflow;
+use super::{Draw, Game};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// The games matching the expression.
+    Filter(Expr),
+    /// The sum of the value over the games matching the expression.
+    Total { value: Value, filter: Expr },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Bool(bool),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Value, Op, Value),
+    Any(Box<Expr>),
+    All(Box<Expr>),
+    Only(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Number(usize),
+    Id,
+    Max(String),
+    Min(String),
+    Sum(String),
+    Power(Vec<String>),
+    /// Cubes of a color in the current draw.
+    Count(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset in the query.
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at {})", self.message, self.position)
+    }
+}
+
+impl std::str::FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::parse(s, Parser::query)
+    }
+}
+
+impl std::str::FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::parse(s, Parser::expr)
+    }
+}
+
+impl Query {
+    /// The sum of the value over the matching games, 0 for a filter. The
+    /// values saturate like `Draw::count`, except `power` which is checked
+    /// like `sum_powers`.
+    pub fn total(&self, games: &[Game]) -> Result<usize, Overflow> {
+        let Query::Total { value, filter } = self else {
+            return Ok(0);
+        };
+
+        run(filter, games).iter().try_fold(0usize, |total, game| {
+            let value = match value {
+                Value::Power(colors) => game.power(&as_strs(colors)),
+                value => Some(value.eval(game, None)),
+            };
+            value
+                .and_then(|value| total.checked_add(value))
+                .ok_or(Overflow { game: game.id })
+        })
+    }
+}
+
+impl Expr {
+    pub fn matches(&self, game: &Game) -> bool {
+        self.eval(game, None)
+    }
+
+    fn eval(&self, game: &Game, draw: Option<&Draw>) -> bool {
+        match self {
+            Expr::Bool(b) => *b,
+            Expr::Not(e) => !e.eval(game, draw),
+            Expr::And(a, b) => a.eval(game, draw) && b.eval(game, draw),
+            Expr::Or(a, b) => a.eval(game, draw) || b.eval(game, draw),
+            Expr::Compare(a, op, b) => {
+                let (a, b) = (a.eval(game, draw), b.eval(game, draw));
+                match op {
+                    Op::Lt => a < b,
+                    Op::Le => a <= b,
+                    Op::Gt => a > b,
+                    Op::Ge => a >= b,
+                    Op::Eq => a == b,
+                    Op::Ne => a != b,
+                }
+            }
+            Expr::Any(e) => game.draws.iter().any(|d| e.eval(game, Some(d))),
+            Expr::All(e) => game.draws.iter().all(|d| e.eval(game, Some(d))),
+            Expr::Only(colors) => game
+                .draws
+                .iter()
+                .flat_map(|d| &d.cubes)
+                .all(|cubes| cubes.count == 0 || colors.contains(&cubes.color)),
+        }
+    }
+}
+
+impl Value {
+    fn eval(&self, game: &Game, draw: Option<&Draw>) -> usize {
+        match self {
+            Value::Number(n) => *n,
+            Value::Id => game.id,
+            Value::Max(color) => game.max(color),
+            Value::Min(color) => game.draws.iter().map(|d| d.count(color)).min().unwrap_or(0),
+            Value::Sum(color) => game
+                .draws
+                .iter()
+                .fold(0, |sum, d| d.count(color).saturating_add(sum)),
+            Value::Power(colors) => game.power(&as_strs(colors)).unwrap_or(usize::MAX),
+            // The parser only accepts colors inside any/all.
+            Value::Count(color) => draw.unwrap().count(color),
+        }
+    }
+}
+
+fn as_strs(colors: &[String]) -> Vec<&str> {
+    colors.iter().map(String::as_str).collect()
+}
+
+/// The games matching `query`.
+pub fn run<'a>(query: &Expr, games: &'a [Game]) -> Vec<&'a Game> {
+    games.iter().filter(|game| query.matches(game)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(usize),
+    Symbol(&'static str),
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    const SYMBOLS: [&str; 9] = ["<=", ">=", "==", "!=", "<", ">", "(", ")", ","];
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < input.len() {
+        let rest = &input[i..];
+        let c = rest.chars().next().unwrap();
+        let word_len = |f: fn(&char) -> bool| rest.chars().take_while(f).map(char::len_utf8).sum();
+
+        if c.is_whitespace() {
+            i += c.len_utf8();
+        } else if c.is_ascii_digit() {
+            let len: usize = word_len(char::is_ascii_digit);
+            let number = rest[..len].parse().map_err(|_| ParseError {
+                position: i,
+                message: format!("Number too big: '{}'", &rest[..len]),
+            })?;
+            tokens.push((i, Token::Number(number)));
+            i += len;
+        } else if c.is_alphabetic() {
+            let len: usize = word_len(|c| c.is_alphanumeric() || *c == '_' || *c == '-');
+            tokens.push((i, Token::Ident(rest[..len].to_owned())));
+            i += len;
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+            tokens.push((i, Token::Symbol(symbol)));
+            i += symbol.len();
+        } else {
+            return Err(ParseError {
+                position: i,
+                message: format!("Unexpected character: '{c}'"),
+            });
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser, one method per rule of the grammar.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    current: usize,
+    /// Whether we are inside an `any`/`all`, where bare colors are allowed.
+    in_draw: bool,
+    /// Position reported for errors at the end of the query.
+    end: usize,
+}
+
+impl Parser {
+    /// Parses the whole of `s` with `rule`.
+    fn parse<T>(s: &str, rule: fn(&mut Parser) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            current: 0,
+            in_draw: false,
+            end: s.len(),
+        };
+        let parsed = rule(&mut parser)?;
+
+        match parser.peek() {
+            None => Ok(parsed),
+            Some(_) => Err(parser.error("Expected the end of the query")),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.current).map(|(_, token)| token)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let position = self.tokens.get(self.current).map_or(self.end, |t| t.0);
+        let found = match self.peek() {
+            Some(Token::Ident(ident)) => format!("'{ident}'"),
+            Some(Token::Number(n)) => format!("'{n}'"),
+            Some(Token::Symbol(s)) => format!("'{s}'"),
+            None => "the end of the query".to_owned(),
+        };
+        ParseError {
+            position,
+            message: format!("{message}, found {found}"),
+        }
+    }
+
+    /// Consumes `token` if it is the next one.
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.current += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), ParseError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            let expected = match token {
+                Token::Symbol(s) => format!("Expected '{s}'"),
+                Token::Ident(s) => format!("Expected '{s}'"),
+                Token::Number(_) => "Expected a number".to_owned(),
+            };
+            Err(self.error(&expected))
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.eat(&Token::Ident(keyword.to_owned()))
+    }
+
+    fn query(&mut self) -> Result<Query, ParseError> {
+        if !self.keyword("total") {
+            return self.expr().map(Query::Filter);
+        }
+
+        let value = self.value()?;
+        let filter = if self.keyword("where") {
+            self.expr()?
+        } else {
+            Expr::Bool(true)
+        };
+        Ok(Query::Total { value, filter })
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.keyword("true") {
+            return Ok(Expr::Bool(true));
+        }
+        if self.keyword("false") {
+            return Ok(Expr::Bool(false));
+        }
+        if self.eat(&Token::Symbol("(")) {
+            let expr = self.expr()?;
+            self.expect(Token::Symbol(")"))?;
+            return Ok(expr);
+        }
+
+        for (keyword, quantifier) in [("any", Expr::Any as fn(_) -> _), ("all", Expr::All)] {
+            if self.keyword(keyword) {
+                self.expect(Token::Symbol("("))?;
+                self.expect(Token::Ident("draw".to_owned()))?;
+                self.expect(Token::Symbol(","))?;
+
+                let outer = std::mem::replace(&mut self.in_draw, true);
+                let expr = self.expr();
+                self.in_draw = outer;
+
+                self.expect(Token::Symbol(")"))?;
+                return Ok(quantifier(Box::new(expr?)));
+            }
+        }
+
+        if self.keyword("only") {
+            return self.colors().map(Expr::Only);
+        }
+
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let left = self.value()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("<")) => Op::Lt,
+            Some(Token::Symbol("<=")) => Op::Le,
+            Some(Token::Symbol(">")) => Op::Gt,
+            Some(Token::Symbol(">=")) => Op::Ge,
+            Some(Token::Symbol("==")) => Op::Eq,
+            Some(Token::Symbol("!=")) => Op::Ne,
+            _ => return Err(self.error("Expected a comparison")),
+        };
+        self.current += 1;
+
+        Ok(Expr::Compare(left, op, self.value()?))
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        let ident = match self.peek() {
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.current += 1;
+                return Ok(Value::Number(n));
+            }
+            Some(Token::Ident(ident)) if !is_keyword(ident) => ident.clone(),
+            _ => return Err(self.error("Expected a value")),
+        };
+
+        let aggregate: Option<fn(String) -> Value> = match ident.as_str() {
+            "max" => Some(Value::Max),
+            "min" => Some(Value::Min),
+            "sum" => Some(Value::Sum),
+            _ => None,
+        };
+
+        if ident == "id" {
+            self.current += 1;
+            Ok(Value::Id)
+        } else if let Some(aggregate) = aggregate {
+            self.current += 1;
+            self.expect(Token::Symbol("("))?;
+            let color = self.color()?;
+            self.expect(Token::Symbol(")"))?;
+            Ok(aggregate(color))
+        } else if ident == "power" {
+            self.current += 1;
+            self.colors().map(Value::Power)
+        } else if self.in_draw {
+            self.current += 1;
+            Ok(Value::Count(ident))
+        } else {
+            Err(self.error("Colors can only be used inside any(draw, ...) or all(draw, ...)"))
+        }
+    }
+
+    /// `"(" color ("," color)* ")"`
+    fn colors(&mut self) -> Result<Vec<String>, ParseError> {
+        self.expect(Token::Symbol("("))?;
+        let mut colors = vec![self.color()?];
+        while self.eat(&Token::Symbol(",")) {
+            colors.push(self.color()?);
+        }
+        self.expect(Token::Symbol(")"))?;
+        Ok(colors)
+    }
+
+    fn color(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Token::Ident(color)) if !is_keyword(color) => {
+                let color = color.clone();
+                self.current += 1;
+                Ok(color)
+            }
+            _ => Err(self.error("Expected a color")),
+        }
+    }
+}
+
+fn is_keyword(ident: &str) -> bool {
+    [
+        "and", "or", "not", "true", "false", "any", "all", "draw", "only", "total", "where",
+    ]
+    .contains(&ident)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_games;
+    use super::*;
+    use crate::tests::EXAMPLE;
+
+    fn matching_ids(query: &str) -> Vec<usize> {
+        let games = parse_games(EXAMPLE).unwrap();
+        let query: Expr = query.parse().unwrap();
+        run(&query, &games).iter().map(|game| game.id).collect()
+    }
+
+    #[test]
+    fn queries() {
+        assert_eq!(matching_ids("max(red) > 12"), vec![3, 4]);
+        assert_eq!(matching_ids("any(draw, blue >= 3 and green == 0)"), vec![1]);
+        assert_eq!(
+            matching_ids("max(red) > 12 or any(draw, blue >= 3 and green == 0)"),
+            vec![1, 3, 4]
+        );
+        assert_eq!(matching_ids("all(draw, red > 0)"), vec![3, 4, 5]);
+        assert_eq!(matching_ids("not (id < 3 or id == 5)"), vec![3, 4]);
+        assert_eq!(matching_ids("min(green) == 0 and sum(blue) == 9"), vec![1]);
+        assert_eq!(matching_ids("max(purple) != 0"), Vec::<usize>::new());
+        assert_eq!(matching_ids("true"), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn totals() {
+        let games = parse_games(EXAMPLE).unwrap();
+        let total = |query: &str| query.parse::<Query>().unwrap().total(&games);
+
+        assert_eq!(total("total id"), Ok(15));
+        assert_eq!(total("total id where max(red) > 12"), Ok(7));
+        assert_eq!(total("total power(red, green, blue)"), Ok(2286));
+        assert_eq!(total("total power(blue) where id <= 2"), Ok(10));
+        assert_eq!(total("max(red) > 12"), Ok(0));
+
+        let games = parse_games("Game 1: 2 red, 0 purple\nGame 2: 1 red, 1 purple").unwrap();
+        let query: Query = "total id where only(red, green)".parse().unwrap();
+        assert_eq!(query.total(&games), Ok(1));
+
+        let games = parse_games(&format!("Game 1: {} red, 2 blue", usize::MAX)).unwrap();
+        let query: Query = "total power(red, blue)".parse().unwrap();
+        assert_eq!(query.total(&games), Err(Overflow { game: 1 }));
+    }
+
+    #[test]
+    fn precedence() {
+        // `and` binds tighter than `or`.
+        assert_eq!(
+            "true or false and false".parse::<Expr>().unwrap(),
+            Expr::Or(
+                Box::new(Expr::Bool(true)),
+                Box::new(Expr::And(
+                    Box::new(Expr::Bool(false)),
+                    Box::new(Expr::Bool(false))
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |query: &str| query.parse::<Expr>().unwrap_err();
+
+        assert_eq!(error("red > 1").position, 0);
+        assert_eq!(error("max(red) > ").position, 11);
+        assert_eq!(error("max(red) 12").position, 9);
+        assert_eq!(error("any(draw, red > 1").position, 17);
+        assert_eq!(error("id > 1 id").position, 7);
+        assert_eq!(error("id ~ 1").position, 3);
+        assert_eq!(error("only()").position, 5);
+        assert_eq!(error("power(red,) > 1").position, 10);
+        assert!("total id where".parse::<Query>().is_err());
+        assert!("total max(red) > 1".parse::<Query>().is_err());
+        assert_eq!(
+            error("max(red) >").message,
+            "Expected a value, found the end of the query"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Images of the schematic, to share outside of a terminal.
+//!
+//! The SVG shows the schematic as text, with the same highlights as the ANSI
+//! rendering. The PPM is a heatmap of symbol density: each cell is colored by
+//! the number of symbols in its 3x3 neighborhood, from black (none) to red to
+//! yellow (all 9).
+
+use aoc2023::grid::Grid;
+
+use super::is_symbol;
+use super::parts::Number;
+use super::rules::Rule;
+
+const CELL_WIDTH: usize = 10;
+const CELL_HEIGHT: usize = 16;
+
+pub fn to_svg(grid: &Grid<char>, numbers: &[Number]) -> String {
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="14">"#,
+        grid.width() * CELL_WIDTH,
+        grid.height() * CELL_HEIGHT
+    );
+    svg.push('\n');
+    svg.push_str(r#"<rect width="100%" height="100%" fill="white"/>"#);
+    svg.push('\n');
+
+    let mut highlight = |row: usize, col: usize, len: usize, color: &str| {
+        svg.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{CELL_HEIGHT}" fill="{color}"/>"#,
+            col * CELL_WIDTH,
+            row * CELL_HEIGHT,
+            len * CELL_WIDTH,
+        ));
+        svg.push('\n');
+    };
+
+    for number in numbers {
+        let color = if number.is_part() {
+            "palegreen"
+        } else {
+            "lightcoral"
+        };
+        highlight(number.row, number.cols.start, number.cols.len(), color);
+    }
+    for &(row, col) in Rule::GEAR.matches(grid, numbers).keys() {
+        highlight(row, col, 1, "gold");
+    }
+
+    for row in 0..grid.height() {
+        let text: String = grid.row(row).iter().map(|&c| escape(c)).collect();
+        svg.push_str(&format!(
+            r#"<text x="0" y="{}" textLength="{}" xml:space="preserve">{text}</text>"#,
+            (row + 1) * CELL_HEIGHT - 4,
+            grid.width() * CELL_WIDTH
+        ));
+        svg.push('\n');
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '&' => "&amp;".to_owned(),
+        '<' => "&lt;".to_owned(),
+        '>' => "&gt;".to_owned(),
+        c => c.to_string(),
+    }
+}
+
+/// A binary PPM (P6) where each cell is a `scale` x `scale` square.
+pub fn to_ppm(grid: &Grid<char>, scale: usize) -> Vec<u8> {
+    let (width, height) = (grid.width() * scale, grid.height() * scale);
+    let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+
+    for y in 0..height {
+        for x in 0..width {
+            let pos = (y / scale, x / scale);
+            let density = grid
+                .neighbors8(pos)
+                .chain([pos])
+                .filter(|&p| is_symbol(grid[p]))
+                .count();
+            ppm.extend(heat(density));
+        }
+    }
+
+    ppm
+}
+
+/// Black for 0, red for 4 or 5, yellow for 9.
+fn heat(density: usize) -> [u8; 3] {
+    let level = density as f64 / 9.0;
+    let red = (level * 2.0).min(1.0);
+    let green = (level * 2.0 - 1.0).max(0.0);
+    [
+        (red * 255.0).round() as u8,
+        (green * 255.0).round() as u8,
+        0,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parts::numbers;
+    use super::*;
+    use aoc2023::grid::Adjacency;
+
+    #[test]
+    fn svg() {
+        let grid = Grid::parse(
+            r#"12*3.
+               &...9"#,
+        );
+        let svg = to_svg(&grid, &numbers(&grid, Adjacency::default()));
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="32""#)
+        );
+        assert!(svg.contains(r#"<rect x="0" y="0" width="20" height="16" fill="palegreen"/>"#));
+        assert!(svg.contains(r#"<rect x="20" y="0" width="10" height="16" fill="gold"/>"#));
+        assert!(svg.contains(r#"<rect x="40" y="16" width="10" height="16" fill="lightcoral"/>"#));
+        assert!(svg.contains(">&amp;...9</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn ppm() {
+        let grid = Grid::parse(
+            r#"#.
+               .."#,
+        );
+        let ppm = to_ppm(&grid, 2);
+        let header = b"P6\n4 4\n255\n";
+
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 4 * 4 * 3);
+        // Every cell sees the only symbol.
+        assert!(ppm[header.len()..].chunks(3).all(|pixel| pixel == heat(1)));
+
+        assert_eq!(heat(0), [0, 0, 0]);
+        assert_eq!(heat(9), [255, 255, 0]);
+    }
+}
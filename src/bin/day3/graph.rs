@@ -0,0 +1,199 @@
+//! The schematic as a graph: numbers and symbols are the nodes, and a number
+//! is linked to each symbol it touches.
+//!
+//! Connected nodes form a machine. They are found with a union-find over the
+//! edges already computed by `parts::numbers`.
+
+use aoc2023::grid::Grid;
+
+use super::parts::{self, Number, Symbol};
+
+/// Numbers and symbols that are connected, directly or not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Component {
+    /// A machine is a component with at least one symbol, a lone number is
+    /// not one.
+    pub fn is_machine(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+
+    /// Sum of the numbers. In a machine, these are all part numbers.
+    pub fn value(&self) -> u64 {
+        self.numbers.iter().map(|n| n.value).sum()
+    }
+}
+
+pub struct Graph {
+    pub components: Vec<Component>,
+}
+
+impl Graph {
+    pub fn new(grid: &Grid<char>, numbers: &[Number]) -> Self {
+        // Nodes are the numbers first, then the symbols.
+        let symbols = parts::symbols(grid);
+        let symbol_node = |symbol: &Symbol| numbers.len() + symbols.binary_search(symbol).unwrap();
+
+        let mut sets = DisjointSets::new(numbers.len() + symbols.len());
+        for (i, number) in numbers.iter().enumerate() {
+            for symbol in &number.symbols {
+                sets.union(i, symbol_node(symbol));
+            }
+        }
+
+        // Components are sorted by their first node: numbers and symbols are
+        // in reading order inside each of them.
+        let mut components: Vec<Component> = Vec::new();
+        let mut component_of_root = vec![None; sets.parents.len()];
+        let mut component = |node| {
+            let root = sets.find(node);
+            *component_of_root[root].get_or_insert_with(|| {
+                components.push(Component {
+                    numbers: Vec::new(),
+                    symbols: Vec::new(),
+                });
+                components.len() - 1
+            })
+        };
+        let number_components: Vec<usize> = (0..numbers.len()).map(&mut component).collect();
+        let symbol_components: Vec<usize> = (0..symbols.len())
+            .map(|i| component(numbers.len() + i))
+            .collect();
+
+        for (number, i) in numbers.iter().zip(number_components) {
+            components[i].numbers.push(number.clone());
+        }
+        for (symbol, i) in symbols.into_iter().zip(symbol_components) {
+            components[i].symbols.push(symbol);
+        }
+
+        Graph { components }
+    }
+
+    /// The machine with the most value, the first one on ties.
+    pub fn largest_machine(&self) -> Option<&Component> {
+        self.components
+            .iter()
+            .filter(|component| component.is_machine())
+            .rev()
+            .max_by_key(|component| component.value())
+    }
+}
+
+/// Union-find with path halving and union by size.
+struct DisjointSets {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        DisjointSets {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parents[node] != node {
+            self.parents[node] = self.parents[self.parents[node]];
+            node = self.parents[node];
+        }
+        node
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+    use aoc2023::grid::Adjacency;
+
+    fn graph(input: &str) -> Graph {
+        let grid = Grid::parse(input);
+        Graph::new(&grid, &parts::numbers(&grid, Adjacency::default()))
+    }
+
+    #[test]
+    fn example() {
+        let graph = graph(EXAMPLE);
+
+        // 114 and 58 are alone, the other numbers are grouped around their
+        // symbol.
+        assert_eq!(graph.components.len(), 8);
+        let values: Vec<u64> = graph.components.iter().map(|c| c.value()).collect();
+        assert_eq!(
+            values,
+            vec![467 + 35, 114, 633, 617, 58, 592, 755 + 598, 664]
+        );
+
+        let largest = graph.largest_machine().unwrap();
+        assert_eq!(largest.value(), 755 + 598);
+        assert_eq!(largest.symbols.len(), 1);
+        assert_eq!(largest.symbols[0].pos, (8, 5));
+    }
+
+    #[test]
+    fn chained_machines() {
+        // 2 links the two gears, # touches nothing.
+        let graph = graph(
+            r#"1*2*3...#
+               ........."#,
+        );
+
+        assert_eq!(graph.components.len(), 2);
+        assert_eq!(graph.components[0].value(), 6);
+        assert_eq!(graph.components[0].symbols.len(), 2);
+        assert_eq!(graph.largest_machine().unwrap().value(), 6);
+        assert!(graph.components[1].numbers.is_empty());
+        assert_eq!(
+            graph.components[1].symbols,
+            vec![Symbol {
+                pos: (0, 8),
+                char: '#'
+            }]
+        );
+    }
+
+    #[test]
+    fn lone_numbers_are_not_machines() {
+        assert!(graph("1").largest_machine().is_none());
+
+        let graph = graph(
+            r#"9999.
+               .....
+               1*1.."#,
+        );
+
+        assert!(!graph.components[0].is_machine());
+        assert_eq!(graph.largest_machine().unwrap().value(), 2);
+        assert_eq!(graph.largest_machine().unwrap().symbols.len(), 1);
+    }
+
+    #[test]
+    fn real_input_components() {
+        let input = std::fs::read_to_string("inputs/day3").unwrap();
+        let grid = Grid::parse(&input);
+        let numbers = parts::numbers(&grid, Adjacency::default());
+        let graph = Graph::new(&grid, &numbers);
+
+        let total: u64 = graph.components.iter().map(|c| c.value()).sum();
+        assert_eq!(total, numbers.iter().map(|n| n.value).sum());
+    }
+}
@@ -0,0 +1,475 @@
+use aoc2023::grid::{Adjacency, Grid};
+
+mod export;
+mod graph;
+mod parts;
+mod render;
+mod rules;
+mod schematic;
+mod stream;
+mod validate;
+
+use rules::Rule;
+
+/// Usage: `cargo run --bin day3 -- [--input <path>] [--pad] [--adjacency 4|8|manhattan=<k>] [--wrap]
+///     [--next-to <symbol>] [--not-parts] [--lonely] [--rule <rule>] [--render] [--svg <path>]
+///     [--ppm <path>] [--set <row>,<col>,<char>]... [--stream] [--machines]`
+///
+/// The schematic is validated first (see `validate.rs`), `--pad` completes
+/// short rows with '.' instead of rejecting them.
+/// `--machines` counts the groups of connected numbers and symbols, and shows
+/// the largest machine (see `graph.rs`) and how many symbols `--lonely` lists.
+/// `--stream` only solves both parts, reading the input one row at a time
+/// without loading or validating it (see `stream.rs`).
+/// `--set` edits a cell and prints the updated sums (see `schematic.rs`).
+/// `--adjacency` and `--wrap` change which cells are adjacent, for everything
+/// but the PPM heatmap.
+/// `--next-to` lists the numbers touching that symbol, `--not-parts` the
+/// numbers touching no symbol and `--lonely` the symbols touching no number.
+/// `--rule` evaluates a symbol rule like `#>=3:sum` (see `rules.rs`).
+/// `--render` prints the schematic with colors (see `render.rs`), `--svg` and
+/// `--ppm` write it as an image (see `export.rs`).
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let value_of = |name: &str| {
+        let i = args.iter().position(|arg| arg == name)?;
+        Some(
+            args.get(i + 1)
+                .unwrap_or_else(|| panic!("{name} needs a value")),
+        )
+    };
+    let path = value_of("--input").map_or("inputs/day3", |path| path);
+
+    if args.iter().any(|arg| arg == "--stream") {
+        let open = || std::io::BufReader::new(std::fs::File::open(path).unwrap());
+        let part_one = stream::part_one_streaming(open());
+        println!("Part 1: sum of part numbers: {part_one}");
+        println!(
+            "Part 2: sum of gear ratios: {}",
+            stream::part_two_streaming(open())
+        );
+        return;
+    }
+
+    let pad = args.iter().any(|arg| arg == "--pad");
+    let mut adjacency = Adjacency::default();
+    if let Some(neighborhood) = value_of("--adjacency") {
+        adjacency.neighborhood = neighborhood.parse().unwrap();
+    }
+    adjacency.wrap = args.iter().any(|arg| arg == "--wrap");
+
+    let grid = match validate::parse_schematic(&std::fs::read_to_string(path).unwrap(), pad) {
+        Ok(grid) => grid,
+        Err(issues) => {
+            for issue in issues {
+                eprintln!("Invalid schematic: {issue}");
+            }
+            std::process::exit(1);
+        }
+    };
+
+    // The parts work on the text of the (padded) schematic.
+    let file_content = grid.to_string();
+    println!("Part 1: sum of part numbers: {}", part_one(&file_content));
+    println!("Part 2: sum of gear ratios: {}", part_two(&file_content));
+
+    if adjacency != Adjacency::default() {
+        println!(
+            "With {adjacency:?}: sum of part numbers: {}, sum of gear ratios: {}",
+            part_one_with(&file_content, adjacency),
+            part_two_with(&file_content, adjacency)
+        );
+    }
+
+    let numbers = parts::numbers(&grid, adjacency);
+    let mut schematic = schematic::Schematic::new(grid.clone(), adjacency);
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "--adjacency" => {
+                args.next();
+            }
+            "--pad" | "--wrap" => (),
+            "--next-to" => {
+                let symbol = args.next().and_then(|s| s.chars().next());
+                let symbol = symbol.expect("--next-to needs a symbol");
+                println!("Numbers next to '{symbol}':");
+                for number in numbers.iter().filter(|n| n.touches(symbol)) {
+                    println!(
+                        "  {} at row {}, cols {:?}",
+                        number.value, number.row, number.cols
+                    );
+                }
+            }
+            "--not-parts" => {
+                println!("Numbers without adjacent symbols:");
+                for number in numbers.iter().filter(|n| !n.is_part()) {
+                    println!(
+                        "  {} at row {}, cols {:?}",
+                        number.value, number.row, number.cols
+                    );
+                }
+            }
+            "--lonely" => {
+                println!("Symbols without adjacent numbers:");
+                for symbol in parts::lonely_symbols(&grid, &numbers) {
+                    println!("  '{}' at {:?}", symbol.char, symbol.pos);
+                }
+            }
+            "--render" => print!("{}", render::render(&grid, &numbers)),
+            "--svg" => {
+                let path = args.next().expect("--svg needs a path");
+                std::fs::write(path, export::to_svg(&grid, &numbers)).unwrap();
+            }
+            "--ppm" => {
+                let path = args.next().expect("--ppm needs a path");
+                std::fs::write(path, export::to_ppm(&grid, 4)).unwrap();
+            }
+            "--set" => {
+                let edit = args.next().expect("--set needs <row>,<col>,<char>");
+                let mut parts = edit.splitn(3, ',');
+                let row = parts.next().unwrap().parse().unwrap();
+                let col = parts.next().unwrap().parse().unwrap();
+                let char = parts.next().and_then(|c| c.chars().next()).unwrap();
+
+                assert!(
+                    schematic.grid().get((row, col)).is_some(),
+                    "({row}, {col}) is outside of the schematic"
+                );
+                schematic.set((row, col), char);
+                println!(
+                    "After setting ({row}, {col}) to '{char}': sum of part numbers: {}, sum of gear ratios: {}",
+                    schematic.part_sum(),
+                    schematic.gear_ratio_sum()
+                );
+            }
+            "--machines" => {
+                let graph = graph::Graph::new(&grid, &numbers);
+                println!("{} connected components", graph.components.len());
+                if let Some(largest) = graph.largest_machine() {
+                    println!(
+                        "Largest machine: {} numbers and {} symbols, worth {}",
+                        largest.numbers.len(),
+                        largest.symbols.len(),
+                        largest.value()
+                    );
+                }
+                let lonely = parts::lonely_symbols(&grid, &numbers);
+                println!("{} symbols without adjacent numbers", lonely.len());
+            }
+            "--rule" => {
+                let rule = args.next().expect("--rule needs a rule");
+                let value = rule.parse::<Rule>().unwrap().evaluate(&grid, &numbers);
+                println!("Rule {rule}: {value}");
+            }
+            _ => panic!("Unknown argument: '{arg}'"),
+        }
+    }
+}
+
+fn is_symbol(c: char) -> bool {
+    !(c.is_ascii_digit() || c == '.')
+}
+
+/// The general idea is to go through the grid one row at a time, without
+/// trying to be too smart.
+///
+/// 1. Mark all the cells that are next to a symbol
+/// 2. Go through each row one cell at a time.
+///     - a. Accumulate the consecutive digits that form a number
+///     - b. Check if each digit is on a marked cell.
+///     - c. Once we reach the end of a number (or of the row), check if it is a
+///       valid part number (ie., it has an adjacent symbol).
+///
+/// Marking the cells up front makes each check a single lookup, instead of
+/// going through the list of all the symbols for each neighbor of each digit.
+fn part_one(input: &str) -> u64 {
+    part_one_with(input, Adjacency::default())
+}
+
+/// `part_one` with other neighbors than the 8 surrounding cells. As all the
+/// neighborhoods are symmetric, the cells next to a symbol are the neighbors of
+/// the symbol.
+fn part_one_with(input: &str, adjacency: Adjacency) -> u64 {
+    let grid = Grid::parse(input);
+    let offsets = grid.neighborhood(adjacency);
+    let mut near_symbol = Grid::new(grid.width(), grid.height(), false);
+    for (pos, _) in grid.iter().filter(|(_, &c)| is_symbol(c)) {
+        for neighbor in grid.neighbors(pos, &offsets) {
+            near_symbol[neighbor] = true;
+        }
+    }
+
+    let mut correct_parts: Vec<u64> = Vec::new();
+
+    for row in 0..grid.height() {
+        let mut current_number = String::new();
+        let mut has_adjacent_symbol = false;
+
+        // The extra '.' ends a number that would end the row.
+        for (col, char) in grid.row(row).iter().copied().chain(['.']).enumerate() {
+            if char.is_ascii_digit() {
+                current_number.push(char);
+                has_adjacent_symbol |= near_symbol[(row, col)];
+            } else {
+                if !current_number.is_empty() && has_adjacent_symbol {
+                    correct_parts.push(current_number.parse().unwrap());
+                }
+
+                // Reset
+                current_number.clear();
+                has_adjacent_symbol = false;
+            }
+        }
+    }
+
+    correct_parts.iter().sum()
+}
+
+/// Gears are '*' symbols touching exactly two numbers, their ratio is the
+/// product of the numbers. See `rules.rs` for other kinds of symbols.
+fn part_two(input: &str) -> u64 {
+    part_two_with(input, Adjacency::default())
+}
+
+fn part_two_with(input: &str, adjacency: Adjacency) -> u64 {
+    let grid = Grid::parse(input);
+    Rule::GEAR.evaluate(&grid, &parts::numbers(&grid, adjacency))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2023::grid::Position;
+    use aoc2023::random::XorShift64;
+
+    pub(crate) const EXAMPLE: &str = r#"467..114..
+                                     ...*......
+                                     ..35..633.
+                                     ......#...
+                                     617*......
+                                     .....+.58.
+                                     ..592.....
+                                     ......755.
+                                     ...$.*....
+                                     .664.598.."#;
+
+    #[test]
+    fn example_part_1() {
+        let input = EXAMPLE;
+
+        assert_eq!(part_one(input), 4361);
+    }
+
+    #[test]
+    fn consider_last_number() {
+        let input = r#"....*...
+                       .....935"#;
+
+        assert_eq!(part_one(input), 935);
+    }
+
+    #[test]
+    fn split_numbers_on_multiple_lines() {
+        let input = r#"...*4
+                       7...."#;
+
+        assert_eq!(part_one(input), 4);
+    }
+
+    #[test]
+    fn example_part_2() {
+        let input = EXAMPLE;
+
+        assert_eq!(part_two(input), 467835);
+    }
+
+    #[test]
+    fn number_touching_two_gears() {
+        // 20 is part of both gears: 10 * 20 + 20 * 30
+        assert_eq!(part_two("10*20*30"), 800);
+
+        // 7 and 8 both touch the two gears: 7 * 8 + 7 * 8
+        let input = r#"..7..
+                       .*.*.
+                       ..8.."#;
+        assert_eq!(part_two(input), 112);
+    }
+
+    #[test]
+    fn other_adjacencies() {
+        use aoc2023::grid::Neighborhood;
+
+        let with = |neighborhood, wrap| Adjacency { neighborhood, wrap };
+
+        // Diagonals only count with 8 neighbors.
+        let input = r#"1..
+                       .#.
+                       ..2"#;
+        assert_eq!(part_one_with(input, with(Neighborhood::Eight, false)), 3);
+        assert_eq!(part_one_with(input, with(Neighborhood::Four, false)), 0);
+
+        // The symbol is 2 cells away from the number.
+        assert_eq!(part_one_with("1.#", with(Neighborhood::Eight, false)), 0);
+        assert_eq!(
+            part_one_with("1.#", with(Neighborhood::Manhattan(2), false)),
+            1
+        );
+
+        // Left and right edges touch when wrapping, so do top and bottom.
+        let input = r#"*3..4
+                       .....
+                       ....."#;
+        assert_eq!(part_one_with(input, with(Neighborhood::Four, false)), 3);
+        assert_eq!(part_two_with(input, with(Neighborhood::Four, false)), 0);
+        assert_eq!(part_one_with(input, with(Neighborhood::Four, true)), 7);
+        assert_eq!(part_two_with(input, with(Neighborhood::Four, true)), 12);
+        let input = r#"*.3..
+                       .....
+                       4...."#;
+        assert_eq!(part_two_with(input, with(Neighborhood::Four, false)), 0);
+        assert_eq!(part_two_with(input, with(Neighborhood::Four, true)), 0);
+        assert_eq!(
+            part_two_with(input, with(Neighborhood::Manhattan(2), true)),
+            12
+        );
+        assert_eq!(part_one_with(input, with(Neighborhood::Four, true)), 4);
+
+        // The default is the puzzle one.
+        let input = std::fs::read_to_string("inputs/day3").unwrap();
+        assert_eq!(
+            part_one_with(&input, Adjacency::default()),
+            part_one(&input)
+        );
+    }
+
+    /// A random schematic, with about 10% of symbols and 30% of digits.
+    fn generate_schematic(seed: u64, width: usize, height: usize) -> String {
+        let mut rng = XorShift64::new(seed);
+
+        let symbols = b"*#+$/@=%-&";
+        let mut schematic = String::with_capacity((width + 1) * height);
+        for _ in 0..height {
+            for _ in 0..width {
+                let r = rng.next_u64();
+                let c = match r % 10 {
+                    0 => symbols[(r / 10 % symbols.len() as u64) as usize],
+                    1..=3 => b'0' + (r / 10 % 10) as u8,
+                    _ => b'.',
+                };
+                schematic.push(c as char);
+            }
+            schematic.push('\n');
+        }
+        schematic
+    }
+
+    /// `part_one` as it was before the lookup grid, to compare the results and
+    /// the timings.
+    fn part_one_symbol_list(input: &str) -> u64 {
+        let grid = Grid::parse(input);
+        let symbol_positions: Vec<Position> = grid
+            .iter()
+            .filter(|(_, &c)| is_symbol(c))
+            .map(|(pos, _)| pos)
+            .collect();
+
+        let mut sum = 0;
+        for row in 0..grid.height() {
+            let mut current_number = String::new();
+            let mut has_adjacent_symbol = false;
+
+            for (col, char) in grid.row(row).iter().copied().chain(['.']).enumerate() {
+                if char.is_ascii_digit() {
+                    current_number.push(char);
+                    has_adjacent_symbol = has_adjacent_symbol
+                        || grid
+                            .neighbors8((row, col))
+                            .any(|neighbor| symbol_positions.contains(&neighbor));
+                } else {
+                    if !current_number.is_empty() && has_adjacent_symbol {
+                        sum += current_number.parse::<u64>().unwrap();
+                    }
+                    current_number.clear();
+                    has_adjacent_symbol = false;
+                }
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn lookup_matches_symbol_list_on_generated_schematics() {
+        for seed in 1..10 {
+            let input = generate_schematic(seed, 40, 30);
+            assert_eq!(
+                part_one(&input),
+                part_one_symbol_list(&input),
+                "seed {seed}"
+            );
+        }
+    }
+
+    /// Run with `cargo test --release --bin day3 -- --ignored --nocapture`.
+    ///
+    /// The symbol list is quadratic, so it only gets a 200x200 schematic: that
+    /// is already slower than the lookup on 1000x1000.
+    #[test]
+    #[ignore]
+    fn bench_part_one() {
+        let small = generate_schematic(7, 200, 200);
+        let start = std::time::Instant::now();
+        let expected = part_one_symbol_list(&small);
+        println!("symbol list, 200x200:  {:?}", start.elapsed());
+
+        let start = std::time::Instant::now();
+        assert_eq!(part_one(&small), expected);
+        println!("lookup, 200x200:       {:?}", start.elapsed());
+
+        let large = generate_schematic(7, 1000, 1000);
+        let start = std::time::Instant::now();
+        part_one(&large);
+        println!("lookup, 1000x1000:     {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn streaming_matches_on_generated_schematics() {
+        for seed in 1..10 {
+            let input = generate_schematic(seed, 40, 30);
+            assert_eq!(
+                stream::part_one_streaming(input.as_bytes()),
+                part_one(&input),
+                "seed {seed}"
+            );
+            assert_eq!(
+                stream::part_two_streaming(input.as_bytes()),
+                part_two(&input),
+                "seed {seed}"
+            );
+        }
+
+        // Much taller than wide.
+        let input = generate_schematic(3, 20, 5000);
+        assert_eq!(
+            stream::part_one_streaming(input.as_bytes()),
+            part_one(&input)
+        );
+        assert_eq!(
+            stream::part_two_streaming(input.as_bytes()),
+            part_two(&input)
+        );
+    }
+
+    #[test]
+    fn real_part_1() {
+        let input = std::fs::read_to_string("inputs/day3").unwrap();
+        assert_eq!(part_one(&input), 527369);
+    }
+
+    #[test]
+    fn real_part_2() {
+        let input = std::fs::read_to_string("inputs/day3").unwrap();
+        assert_eq!(part_two(&input), 73074886);
+    }
+}
@@ -0,0 +1,176 @@
+//! Every number of a schematic, with where it is and what it touches.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use aoc2023::grid::{Adjacency, Grid, Offsets, Position};
+
+use super::is_symbol;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol {
+    pub pos: Position,
+    pub char: char,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub row: usize,
+    /// Columns of the digits.
+    pub cols: Range<usize>,
+    pub value: u64,
+    /// Symbols adjacent to any of the digits, sorted by position.
+    pub symbols: Vec<Symbol>,
+}
+
+impl Number {
+    /// A part number is a number with at least one adjacent symbol.
+    pub fn is_part(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+
+    pub fn touches(&self, symbol: char) -> bool {
+        self.symbols.iter().any(|s| s.char == symbol)
+    }
+}
+
+/// All the numbers, row after row. A number never continues on the next row,
+/// even if the edges wrap around.
+pub fn numbers(grid: &Grid<char>, adjacency: Adjacency) -> Vec<Number> {
+    let mut numbers = Vec::new();
+    let offsets = grid.neighborhood(adjacency);
+
+    for row in 0..grid.height() {
+        let mut start = None;
+
+        // The extra '.' ends a number that would end the row.
+        for (col, char) in grid.row(row).iter().copied().chain(['.']).enumerate() {
+            match (char.is_ascii_digit(), start) {
+                (true, None) => start = Some(col),
+                (false, Some(first)) => {
+                    numbers.push(number(grid, &offsets, row, first..col));
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    numbers
+}
+
+/// The number whose digits are in `cols` of `row`, `offsets` being the
+/// neighborhood of the grid.
+pub fn number(grid: &Grid<char>, offsets: &Offsets, row: usize, cols: Range<usize>) -> Number {
+    let value = grid.row(row)[cols.clone()]
+        .iter()
+        .collect::<String>()
+        .parse()
+        .unwrap();
+
+    let mut symbols: Vec<Symbol> = cols
+        .clone()
+        .flat_map(|col| grid.neighbors((row, col), offsets))
+        .filter(|&pos| is_symbol(grid[pos]))
+        .map(|pos| Symbol {
+            pos,
+            char: grid[pos],
+        })
+        .collect();
+    symbols.sort();
+    symbols.dedup();
+
+    Number {
+        row,
+        cols,
+        value,
+        symbols,
+    }
+}
+
+/// All the symbols, row after row.
+pub fn symbols(grid: &Grid<char>) -> Vec<Symbol> {
+    grid.iter()
+        .filter(|(_, &c)| is_symbol(c))
+        .map(|(pos, &char)| Symbol { pos, char })
+        .collect()
+}
+
+/// Symbols that don't touch any number.
+pub fn lonely_symbols(grid: &Grid<char>, numbers: &[Number]) -> Vec<Symbol> {
+    let touched: HashSet<Position> = numbers
+        .iter()
+        .flat_map(|n| n.symbols.iter().map(|s| s.pos))
+        .collect();
+
+    symbols(grid)
+        .into_iter()
+        .filter(|symbol| !touched.contains(&symbol.pos))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+
+    #[test]
+    fn numbers_of_example() {
+        let numbers = numbers(&Grid::parse(EXAMPLE), Adjacency::default());
+
+        assert_eq!(numbers.len(), 10);
+        assert_eq!(
+            numbers[0],
+            Number {
+                row: 0,
+                cols: 0..3,
+                value: 467,
+                symbols: vec![Symbol {
+                    pos: (1, 3),
+                    char: '*'
+                }]
+            }
+        );
+        assert!(!numbers[1].is_part());
+        assert_eq!(numbers[1].value, 114);
+
+        let next_to_hash: Vec<u64> = numbers
+            .iter()
+            .filter(|n| n.touches('#'))
+            .map(|n| n.value)
+            .collect();
+        assert_eq!(next_to_hash, vec![633]);
+
+        let sum: u64 = numbers
+            .iter()
+            .filter(|n| n.is_part())
+            .map(|n| n.value)
+            .sum();
+        assert_eq!(sum, 4361);
+    }
+
+    #[test]
+    fn lonely() {
+        let grid = Grid::parse(
+            r#"#..12
+               ....*
+               .$..."#,
+        );
+        let numbers = numbers(&grid, Adjacency::default());
+
+        assert_eq!(
+            lonely_symbols(&grid, &numbers),
+            vec![
+                Symbol {
+                    pos: (0, 0),
+                    char: '#'
+                },
+                Symbol {
+                    pos: (2, 1),
+                    char: '$'
+                }
+            ]
+        );
+        assert_eq!(numbers[0].cols, 3..5);
+    }
+}
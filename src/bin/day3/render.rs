@@ -0,0 +1,104 @@
+//! The schematic in color, to see what the algorithms see.
+//!
+//! Part numbers are green, numbers without symbol are red and gears are bold
+//! yellow. The ratio of the gears of a row is written on the right of it.
+
+use aoc2023::grid::Grid;
+
+use super::parts::Number;
+use super::rules::Rule;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Plain,
+    Part,
+    NotPart,
+    Gear,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Plain => RESET,
+            Style::Part => GREEN,
+            Style::NotPart => RED,
+            Style::Gear => BOLD_YELLOW,
+        }
+    }
+}
+
+pub fn render(grid: &Grid<char>, numbers: &[Number]) -> String {
+    let mut styles = Grid::new(grid.width(), grid.height(), Style::Plain);
+    for number in numbers {
+        let style = if number.is_part() {
+            Style::Part
+        } else {
+            Style::NotPart
+        };
+        for col in number.cols.clone() {
+            styles[(number.row, col)] = style;
+        }
+    }
+
+    let gears = Rule::GEAR.matches(grid, numbers);
+    for &pos in gears.keys() {
+        styles[pos] = Style::Gear;
+    }
+
+    let mut rendered = String::new();
+    for row in 0..grid.height() {
+        let mut current = Style::Plain;
+        for (&char, &style) in grid.row(row).iter().zip(styles.row(row)) {
+            if style != current {
+                rendered.push_str(style.code());
+                current = style;
+            }
+            rendered.push(char);
+        }
+        if current != Style::Plain {
+            rendered.push_str(RESET);
+        }
+
+        let legend: Vec<String> = gears
+            .range((row, 0)..(row + 1, 0))
+            .map(|((_, col), values)| {
+                let ratio = Rule::GEAR.combine(values);
+                format!("col {col}: {} x {} = {ratio}", values[0], values[1])
+            })
+            .collect();
+        if !legend.is_empty() {
+            rendered.push_str(&format!("   {BOLD_YELLOW}*{RESET} {}", legend.join(", ")));
+        }
+        rendered.push('\n');
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parts::numbers;
+    use super::*;
+    use aoc2023::grid::Adjacency;
+
+    #[test]
+    fn render_small_schematic() {
+        let grid = Grid::parse(
+            r#"12*3.
+               ....9"#,
+        );
+
+        assert_eq!(
+            render(&grid, &numbers(&grid, Adjacency::default())),
+            format!(
+                "{GREEN}12{BOLD_YELLOW}*{GREEN}3{RESET}.   {BOLD_YELLOW}*{RESET} col 2: 12 x 3 = 36\n\
+                 ....{RED}9{RESET}\n"
+            )
+        );
+    }
+}
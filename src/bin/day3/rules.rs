@@ -0,0 +1,168 @@
+//! Symbols that combine the numbers around them, like gears.
+//!
+//! A rule says which symbol it applies to, how many adjacent numbers the
+//! symbol needs, and how to combine them. Gears are `*=2:product`: a `*`
+//! touching exactly two numbers is worth their product. The rules sum the
+//! values of all the matching symbols of the schematic.
+
+use std::collections::BTreeMap;
+
+use aoc2023::grid::{Grid, Position};
+
+use super::parts::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Count {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub symbol: char,
+    pub count: Count,
+    pub combine: Combine,
+}
+
+impl Rule {
+    pub const GEAR: Rule = Rule {
+        symbol: '*',
+        count: Count::Exactly(2),
+        combine: Combine::Product,
+    };
+
+    /// Sum of the values of all the symbols matching the rule.
+    pub fn evaluate(&self, grid: &Grid<char>, numbers: &[Number]) -> u64 {
+        self.matches(grid, numbers)
+            .values()
+            .map(|values| self.combine(values))
+            .sum()
+    }
+
+    /// The symbols matching the rule with the values of their numbers.
+    pub fn matches(&self, grid: &Grid<char>, numbers: &[Number]) -> BTreeMap<Position, Vec<u64>> {
+        let mut adjacent: BTreeMap<Position, Vec<u64>> = grid
+            .iter()
+            .filter(|(_, &c)| c == self.symbol)
+            .map(|(pos, _)| (pos, Vec::new()))
+            .collect();
+
+        // A number touching several symbols counts for each of them.
+        for number in numbers {
+            for symbol in number.symbols.iter().filter(|s| s.char == self.symbol) {
+                adjacent.get_mut(&symbol.pos).unwrap().push(number.value);
+            }
+        }
+
+        adjacent.retain(|_, values| match self.count {
+            Count::Exactly(n) => values.len() == n,
+            Count::AtLeast(n) => values.len() >= n,
+        });
+        adjacent
+    }
+
+    pub fn combine(&self, values: &[u64]) -> u64 {
+        match self.combine {
+            Combine::Product => values.iter().product(),
+            Combine::Sum => values.iter().sum(),
+            Combine::Max => values.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+impl std::str::FromStr for Rule {
+    type Err = String;
+
+    /// `<symbol>=<n>:<combine>` or `<symbol>>=<n>:<combine>`, with `combine`
+    /// being `product`, `sum` or `max`. For instance `#>=3:sum`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid rule: '{s}', expected something like '*=2:product'");
+
+        let mut chars = s.chars();
+        let symbol = chars.next().ok_or_else(error)?;
+        let (count, combine) = chars.as_str().split_once(':').ok_or_else(error)?;
+
+        let count = if let Some(n) = count.strip_prefix(">=") {
+            Count::AtLeast(n.parse().map_err(|_| error())?)
+        } else if let Some(n) = count.strip_prefix('=') {
+            Count::Exactly(n.parse().map_err(|_| error())?)
+        } else {
+            return Err(error());
+        };
+
+        let combine = match combine {
+            "product" => Combine::Product,
+            "sum" => Combine::Sum,
+            "max" => Combine::Max,
+            _ => return Err(error()),
+        };
+
+        Ok(Rule {
+            symbol,
+            count,
+            combine,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parts::numbers;
+    use super::*;
+    use crate::tests::EXAMPLE;
+    use aoc2023::grid::Adjacency;
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!("*=2:product".parse(), Ok(Rule::GEAR));
+        assert_eq!(
+            "#>=3:sum".parse(),
+            Ok(Rule {
+                symbol: '#',
+                count: Count::AtLeast(3),
+                combine: Combine::Sum
+            })
+        );
+        assert!("#3:sum".parse::<Rule>().is_err());
+        assert!("#=3:avg".parse::<Rule>().is_err());
+        assert!("".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn other_rules_on_example() {
+        let grid = Grid::parse(EXAMPLE);
+        let numbers = numbers(&grid, Adjacency::default());
+        let rule = |rule: &str| rule.parse::<Rule>().unwrap().evaluate(&grid, &numbers);
+
+        assert_eq!(rule("*=2:product"), 467835);
+        // The '*' at (4, 3) only touches 617.
+        assert_eq!(rule("*=1:sum"), 617);
+        assert_eq!(rule("*>=1:max"), 467 + 617 + 755);
+        assert_eq!(rule("#=1:sum"), 633);
+        assert_eq!(rule("#>=3:sum"), 0);
+    }
+
+    #[test]
+    fn hash_with_three_numbers() {
+        let grid = Grid::parse(
+            r#"1.2
+               .#.
+               ..3"#,
+        );
+        let numbers = numbers(&grid, Adjacency::default());
+
+        let rule: Rule = "#=3:product".parse().unwrap();
+        assert_eq!(rule.evaluate(&grid, &numbers), 6);
+        let rule: Rule = "#>=2:sum".parse().unwrap();
+        assert_eq!(rule.evaluate(&grid, &numbers), 6);
+        let rule: Rule = "#=2:sum".parse().unwrap();
+        assert_eq!(rule.evaluate(&grid, &numbers), 0);
+    }
+}
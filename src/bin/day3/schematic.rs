@@ -0,0 +1,259 @@
+//! A schematic that keeps its sums up to date when a cell changes.
+//!
+//! Editing a cell can only change:
+//! - the numbers on the same row that contain the cell or are right next to
+//!   it (a digit can merge two numbers, a '.' can split one),
+//! - the symbols of the numbers around the cell,
+//! - the gears around the cell and around the numbers above.
+//!
+//! So instead of re-running `part_one` and `part_two`, we remove these numbers
+//! and gears, edit the cell, and add them back.
+
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use aoc2023::grid::{Adjacency, Grid, Offsets, Position};
+
+use super::parts::{self, Number};
+
+pub struct Schematic {
+    grid: Grid<char>,
+    offsets: Offsets,
+    /// For each digit, the ID of its number in `numbers`.
+    owners: Grid<Option<usize>>,
+    numbers: HashMap<usize, Number>,
+    next_id: usize,
+    gear_ratios: HashMap<Position, u64>,
+    part_sum: u64,
+    gear_ratio_sum: u64,
+}
+
+impl Schematic {
+    pub fn new(grid: Grid<char>, adjacency: Adjacency) -> Self {
+        let mut schematic = Schematic {
+            owners: Grid::new(grid.width(), grid.height(), None),
+            numbers: HashMap::new(),
+            next_id: 0,
+            gear_ratios: HashMap::new(),
+            part_sum: 0,
+            gear_ratio_sum: 0,
+            offsets: grid.neighborhood(adjacency),
+            grid,
+        };
+
+        for number in parts::numbers(&schematic.grid, adjacency) {
+            schematic.insert(number);
+        }
+        let gears: Vec<Position> = schematic
+            .grid
+            .iter()
+            .filter(|(_, &c)| c == '*')
+            .map(|(pos, _)| pos)
+            .collect();
+        for gear in gears {
+            schematic.update_gear(gear);
+        }
+
+        schematic
+    }
+
+    /// Same as `part_one`.
+    pub fn part_sum(&self) -> u64 {
+        self.part_sum
+    }
+
+    /// Same as `part_two`.
+    pub fn gear_ratio_sum(&self) -> u64 {
+        self.gear_ratio_sum
+    }
+
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    pub fn set(&mut self, (row, col): Position, char: char) {
+        if self.grid[(row, col)] == char {
+            return;
+        }
+
+        let mut gears: BTreeSet<Position> = BTreeSet::from([(row, col)]);
+        gears.extend(self.grid.neighbors((row, col), &self.offsets));
+
+        // Numbers of the row that can merge or split.
+        let mut span = col..col + 1;
+        let first_col = col.saturating_sub(1);
+        let last_col = (col + 1).min(self.grid.width() - 1);
+        for c in first_col..=last_col {
+            if let Some(id) = self.owners[(row, c)] {
+                let number = self.remove(id);
+                gears.extend(number.symbols.iter().map(|s| s.pos));
+                span = span.start.min(number.cols.start)..span.end.max(number.cols.end);
+            }
+        }
+
+        self.grid[(row, col)] = char;
+
+        for number in self.scan(row, span) {
+            gears.extend(number.symbols.iter().map(|s| s.pos));
+            self.insert(number);
+        }
+
+        // Numbers around the cell, which might have gained or lost a symbol.
+        let around: BTreeSet<usize> = self
+            .grid
+            .neighbors((row, col), &self.offsets)
+            .filter_map(|pos| self.owners[pos])
+            .collect();
+        for id in around {
+            let number = self.remove(id);
+            let number = parts::number(&self.grid, &self.offsets, number.row, number.cols);
+            self.insert(number);
+        }
+
+        for gear in gears {
+            self.update_gear(gear);
+        }
+    }
+
+    /// The numbers in `cols` of `row`.
+    fn scan(&self, row: usize, cols: Range<usize>) -> Vec<Number> {
+        let mut numbers = Vec::new();
+        let mut start = None;
+
+        for col in cols.start..=cols.end {
+            let is_digit = col < cols.end && self.grid[(row, col)].is_ascii_digit();
+            match (is_digit, start) {
+                (true, None) => start = Some(col),
+                (false, Some(first)) => {
+                    numbers.push(parts::number(&self.grid, &self.offsets, row, first..col));
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+
+        numbers
+    }
+
+    fn insert(&mut self, number: Number) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        for col in number.cols.clone() {
+            self.owners[(number.row, col)] = Some(id);
+        }
+        if number.is_part() {
+            self.part_sum += number.value;
+        }
+        self.numbers.insert(id, number);
+    }
+
+    fn remove(&mut self, id: usize) -> Number {
+        let number = self.numbers.remove(&id).unwrap();
+
+        for col in number.cols.clone() {
+            self.owners[(number.row, col)] = None;
+        }
+        if number.is_part() {
+            self.part_sum -= number.value;
+        }
+        number
+    }
+
+    /// Recomputes the ratio of the gear that is, or was, at `pos`.
+    fn update_gear(&mut self, pos: Position) {
+        if let Some(ratio) = self.gear_ratios.remove(&pos) {
+            self.gear_ratio_sum -= ratio;
+        }
+        if self.grid[pos] != '*' {
+            return;
+        }
+
+        let adjacent: BTreeSet<usize> = self
+            .grid
+            .neighbors(pos, &self.offsets)
+            .filter_map(|neighbor| self.owners[neighbor])
+            .collect();
+        if let [a, b] = adjacent.into_iter().collect::<Vec<_>>()[..] {
+            let ratio = self.numbers[&a].value * self.numbers[&b].value;
+            self.gear_ratios.insert(pos, ratio);
+            self.gear_ratio_sum += ratio;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rules::Rule;
+    use super::*;
+    use crate::tests::EXAMPLE;
+    use aoc2023::grid::Neighborhood;
+    use aoc2023::random::XorShift64;
+
+    fn full_recompute(grid: &Grid<char>, adjacency: Adjacency) -> (u64, u64) {
+        let numbers = parts::numbers(grid, adjacency);
+        let part_sum = numbers
+            .iter()
+            .filter(|n| n.is_part())
+            .map(|n| n.value)
+            .sum();
+        (part_sum, Rule::GEAR.evaluate(grid, &numbers))
+    }
+
+    #[test]
+    fn example() {
+        let grid = Grid::parse(EXAMPLE);
+        let mut schematic = Schematic::new(grid, Adjacency::default());
+        assert_eq!(
+            (schematic.part_sum(), schematic.gear_ratio_sum()),
+            (4361, 467835)
+        );
+
+        // 114 becomes a part number.
+        schematic.set((1, 5), '#');
+        assert_eq!(schematic.part_sum(), 4361 + 114);
+
+        // 467 and 114 are merged.
+        schematic.set((0, 3), '1');
+        schematic.set((0, 4), '2');
+        assert_eq!(schematic.part_sum(), 4361 - 467 + 46712114);
+        assert_eq!(schematic.gear_ratio_sum(), 467835 - 16345 + 46712114 * 35);
+
+        // The first gear is removed.
+        schematic.set((1, 3), '.');
+        assert_eq!(schematic.gear_ratio_sum(), 467835 - 16345);
+    }
+
+    #[test]
+    fn random_edits_match_full_recompute() {
+        let mut rng = XorShift64::new(42);
+        let chars = b"0123456789.....*#";
+
+        for adjacency in [
+            Adjacency::default(),
+            Adjacency {
+                neighborhood: Neighborhood::Manhattan(2),
+                wrap: true,
+            },
+        ] {
+            let (width, height) = (12, 8);
+            let mut grid = Grid::new(width, height, '.');
+            for pos in grid.positions().collect::<Vec<_>>() {
+                grid[pos] = chars[rng.below(chars.len())] as char;
+            }
+
+            let mut schematic = Schematic::new(grid, adjacency);
+            for _ in 0..500 {
+                let pos = (rng.below(height), rng.below(width));
+                schematic.set(pos, chars[rng.below(chars.len())] as char);
+
+                assert_eq!(
+                    (schematic.part_sum(), schematic.gear_ratio_sum()),
+                    full_recompute(schematic.grid(), adjacency),
+                    "after setting {pos:?} in\n{}",
+                    schematic.grid()
+                );
+            }
+        }
+    }
+}
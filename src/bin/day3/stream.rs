@@ -0,0 +1,159 @@
+//! Both parts on a schematic read one row at a time.
+//!
+//! A number can only touch a symbol of the row above, of its own row, or of
+//! the row below, and so can a gear. So we only keep three rows in memory and
+//! look at the middle one: the memory used depends on the width of the
+//! schematic, not its height.
+//!
+//! This only handles the puzzle adjacency (8 neighbors, no wrapping).
+
+use std::io::BufRead;
+use std::ops::Range;
+
+use super::is_symbol;
+
+/// Same as `part_one`.
+pub fn part_one_streaming<R: BufRead>(reader: R) -> u64 {
+    let mut sum = 0;
+
+    for_each_window(reader, |window| {
+        for (cols, value) in &window[1].numbers {
+            let around = cols.start.saturating_sub(1)..cols.end + 1;
+            let touches_symbol = window.iter().any(|row| {
+                row.cells
+                    .get(around.start..around.end.min(row.cells.len()))
+                    .is_some_and(|cells| cells.iter().any(|&c| is_symbol(c as char)))
+            });
+            if touches_symbol {
+                sum += value;
+            }
+        }
+    });
+
+    sum
+}
+
+/// Same as `part_two`.
+pub fn part_two_streaming<R: BufRead>(reader: R) -> u64 {
+    let mut sum = 0;
+
+    for_each_window(reader, |window| {
+        for (col, _) in window[1]
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c == b'*')
+        {
+            let adjacent: Vec<u64> = window.iter().flat_map(|row| row.around(col)).collect();
+            if let [a, b] = adjacent[..] {
+                sum += a * b;
+            }
+        }
+    });
+
+    sum
+}
+
+/// A row of the window, with its numbers found once when it is read.
+#[derive(Default)]
+struct Row {
+    cells: Vec<u8>,
+    /// Columns and value of each number, from left to right.
+    numbers: Vec<(Range<usize>, u64)>,
+}
+
+impl Row {
+    /// Values of the numbers touching `col` or one of the columns next to it.
+    fn around(&self, col: usize) -> impl Iterator<Item = u64> + '_ {
+        // The numbers are sorted and don't overlap, so they are sorted by end
+        // too: skip the ones ending before `col - 1`.
+        let first = self.numbers.partition_point(|(cols, _)| cols.end < col);
+        self.numbers[first..]
+            .iter()
+            .take_while(move |(cols, _)| cols.start <= col + 1)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Calls `visit` with the rows above, at and below each row. The rows are
+/// trimmed and empty lines are skipped, like `Grid::parse` does. Missing rows
+/// (above the first one and below the last one) are empty.
+fn for_each_window<R: BufRead>(mut reader: R, mut visit: impl FnMut([&Row; 3])) {
+    let mut window: [Row; 3] = Default::default();
+    let mut line = String::new();
+
+    // Fills `row` with the next non-empty row, or leaves it empty at the end.
+    let mut next_row = |row: &mut Row| {
+        row.cells.clear();
+        while row.cells.is_empty() {
+            line.clear();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            row.cells.extend_from_slice(line.trim().as_bytes());
+        }
+        row.numbers.clear();
+        row.numbers.extend(numbers(&row.cells));
+    };
+
+    next_row(&mut window[2]);
+    loop {
+        // The row below becomes the current one, the row above is reused.
+        window.rotate_left(1);
+        next_row(&mut window[2]);
+        if window[1].cells.is_empty() {
+            break;
+        }
+        visit([&window[0], &window[1], &window[2]]);
+    }
+}
+
+/// The columns and value of each number of a row.
+fn numbers(row: &[u8]) -> impl Iterator<Item = (Range<usize>, u64)> + '_ {
+    let mut col = 0;
+
+    std::iter::from_fn(move || {
+        while col < row.len() && !row[col].is_ascii_digit() {
+            col += 1;
+        }
+        let start = col;
+        let mut value = 0;
+        while col < row.len() && row[col].is_ascii_digit() {
+            value = value * 10 + (row[col] - b'0') as u64;
+            col += 1;
+        }
+        (start < col).then_some((start..col, value))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+
+    #[test]
+    fn example() {
+        assert_eq!(part_one_streaming(EXAMPLE.as_bytes()), 4361);
+        assert_eq!(part_two_streaming(EXAMPLE.as_bytes()), 467835);
+    }
+
+    #[test]
+    fn edges() {
+        assert_eq!(part_one_streaming("....*...\n.....935".as_bytes()), 935);
+        assert_eq!(part_one_streaming("...*4\n7....".as_bytes()), 4);
+        assert_eq!(part_two_streaming("10*20*30".as_bytes()), 800);
+        assert_eq!(part_one_streaming("".as_bytes()), 0);
+        assert_eq!(part_one_streaming("\n12#\n\n".as_bytes()), 12);
+
+        // Numbers ending right before the gear, or starting right after it.
+        assert_eq!(part_two_streaming("1...\n.*..\n..2.".as_bytes()), 2);
+        assert_eq!(part_two_streaming("1....\n..*..\n....2".as_bytes()), 0);
+    }
+
+    #[test]
+    fn real() {
+        let open = || std::io::BufReader::new(std::fs::File::open("inputs/day3").unwrap());
+        assert_eq!(part_one_streaming(open()), 527369);
+        assert_eq!(part_two_streaming(open()), 73074886);
+    }
+}
@@ -0,0 +1,192 @@
+//! Checks that a schematic is a proper rectangle of visible ASCII chars
+//! before we start computing positions in it.
+//!
+//! Lines are trimmed of surrounding spaces (inputs are indented in the tests)
+//! and empty lines are skipped, like `Grid::parse` does.
+
+use aoc2023::grid::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// The row doesn't have the same length as the first one.
+    RaggedRow {
+        row: usize,
+        length: usize,
+        expected: usize,
+    },
+    NonAscii {
+        row: usize,
+        col: usize,
+        char: char,
+    },
+    /// Whitespace or control char inside a row, e.g. a tab. It would be read
+    /// as a symbol otherwise.
+    Invisible {
+        row: usize,
+        col: usize,
+        char: char,
+    },
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::RaggedRow {
+                row,
+                length,
+                expected,
+            } => write!(f, "row {row} has {length} cells, expected {expected}"),
+            Issue::NonAscii { row, col, char } => {
+                write!(f, "non-ASCII char {char:?} at row {row}, col {col}")
+            }
+            Issue::Invisible { row, col, char } => {
+                write!(f, "invisible char {char:?} at row {row}, col {col}")
+            }
+        }
+    }
+}
+
+fn rows(input: &str) -> Vec<&str> {
+    input
+        .lines()
+        .map(|line| line.trim_matches(' '))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// All the issues, row after row. With `pad`, short rows are not an issue:
+/// the expected width is the one of the longest row.
+pub fn validate(input: &str, pad: bool) -> Vec<Issue> {
+    let rows = rows(input);
+    let lengths: Vec<usize> = rows.iter().map(|row| row.chars().count()).collect();
+    let expected = if pad {
+        lengths.iter().copied().max().unwrap_or(0)
+    } else {
+        lengths.first().copied().unwrap_or(0)
+    };
+
+    let mut issues = Vec::new();
+    for (row, line) in rows.iter().enumerate() {
+        for (col, char) in line.chars().enumerate() {
+            if char.is_whitespace() || char.is_control() {
+                issues.push(Issue::Invisible { row, col, char });
+            } else if !char.is_ascii() {
+                issues.push(Issue::NonAscii { row, col, char });
+            }
+        }
+
+        if lengths[row] != expected && !(pad && lengths[row] < expected) {
+            issues.push(Issue::RaggedRow {
+                row,
+                length: lengths[row],
+                expected,
+            });
+        }
+    }
+
+    issues
+}
+
+/// The grid of a valid schematic. With `pad`, short rows are completed with
+/// '.' up to the width of the longest row.
+pub fn parse_schematic(input: &str, pad: bool) -> Result<Grid<char>, Vec<Issue>> {
+    let issues = validate(input, pad);
+    if !issues.is_empty() {
+        return Err(issues);
+    }
+
+    let rows = rows(input);
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let padded: Vec<String> = rows.iter().map(|row| format!("{row:.<width$}")).collect();
+
+    Ok(Grid::parse(&padded.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let input = r#"467..114..
+                       ...*......"#;
+
+        assert_eq!(validate(input, false), vec![]);
+        assert_eq!(parse_schematic(input, false), Ok(Grid::parse(input)));
+    }
+
+    #[test]
+    fn ragged() {
+        let input = r#"467..114..
+                       ...*.....
+                       ..35..633..."#;
+
+        assert_eq!(
+            validate(input, false),
+            vec![
+                Issue::RaggedRow {
+                    row: 1,
+                    length: 9,
+                    expected: 10
+                },
+                Issue::RaggedRow {
+                    row: 2,
+                    length: 12,
+                    expected: 10
+                }
+            ]
+        );
+
+        let grid = parse_schematic(input, true).unwrap();
+        assert_eq!(grid.width(), 12);
+        assert_eq!(grid.row(1).iter().collect::<String>(), "...*........");
+    }
+
+    #[test]
+    fn bad_chars() {
+        let input = "467..114..\n...*\t.....\n..35..6é3.";
+
+        assert_eq!(
+            validate(input, true),
+            vec![
+                Issue::Invisible {
+                    row: 1,
+                    col: 4,
+                    char: '\t'
+                },
+                Issue::NonAscii {
+                    row: 2,
+                    col: 7,
+                    char: 'é'
+                }
+            ]
+        );
+        assert_eq!(
+            Issue::NonAscii {
+                row: 2,
+                col: 7,
+                char: 'é'
+            }
+            .to_string(),
+            "non-ASCII char 'é' at row 2, col 7"
+        );
+        assert!(parse_schematic(input, true).is_err());
+
+        // Spaces used to be read as symbols.
+        assert_eq!(
+            validate("12 ..\n.\u{7}...", false),
+            vec![
+                Issue::Invisible {
+                    row: 0,
+                    col: 2,
+                    char: ' '
+                },
+                Issue::Invisible {
+                    row: 1,
+                    col: 1,
+                    char: '\u{7}'
+                }
+            ]
+        );
+    }
+}
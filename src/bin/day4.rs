@@ -6,58 +6,69 @@ fn main() {
     println!("Part 2: {}", part_two(&file_content));
 }
 
+#[derive(Debug, PartialEq, Eq)]
+struct Card {
+    id: usize,
+    winning: BTreeSet<u32>,
+    drawn: BTreeSet<u32>,
+}
+
+impl Card {
+    /// How many drawn numbers are winning numbers.
+    fn matches(&self) -> usize {
+        self.winning.intersection(&self.drawn).count()
+    }
+}
+
+fn parse_cards(input: &str) -> Vec<Card> {
+    input.lines().map(parse_card).collect()
+}
+
+/// Parses `Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53`.
+fn parse_card(line: &str) -> Card {
+    let (name, data) = line.split_once(": ").unwrap();
+    let (left_numbers, right_numbers) = data.split_once('|').unwrap();
+    let numbers = |numbers: &str| {
+        numbers
+            .split_ascii_whitespace()
+            .map(|n| n.parse::<u32>().unwrap())
+            .collect::<BTreeSet<_>>()
+    };
+
+    Card {
+        id: name
+            .trim()
+            .strip_prefix("Card")
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap(),
+        winning: numbers(left_numbers),
+        drawn: numbers(right_numbers),
+    }
+}
+
 fn part_one(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|line| {
-            let (_card_id, data) = line.split_once(": ").unwrap();
-            let (left_numbers, right_numbers) = data.split_once("|").unwrap();
-
-            let winning_numbers_hash = left_numbers
-                .split_ascii_whitespace()
-                .map(|n| n.parse::<u32>().unwrap())
-                .collect::<BTreeSet<_>>();
-            let drawed_number = right_numbers
-                .split_ascii_whitespace()
-                .map(|n| n.parse::<u32>().unwrap())
-                .collect::<BTreeSet<_>>();
-
-            let matching_count = winning_numbers_hash.intersection(&drawed_number).count();
-
-            if matching_count > 0 {
-                2u64.pow((matching_count - 1) as u32)
-            } else {
-                0
-            }
+    parse_cards(input)
+        .iter()
+        .map(|card| match card.matches() {
+            0 => 0,
+            matching_count => 2u64.pow((matching_count - 1) as u32),
         })
         .sum()
 }
 
 /// The idea is to update the `number_of_draws_per_card` after we draw a card.
+/// All the copies of a card win the same following cards, so each of these gets
+/// as many new copies in one step.
 fn part_two(input: &str) -> u64 {
-    let mut number_of_draws_per_card = vec![1; input.lines().count()];
+    let cards = parse_cards(input);
+    let mut number_of_draws_per_card = vec![1; cards.len()];
 
-    for (i, line) in input.lines().enumerate() {
-        let (_card_name, data) = line.split_once(": ").unwrap();
-        let (left_numbers, right_numbers) = data.split_once("|").unwrap();
-        let winning_numbers_hash = left_numbers
-            .split_ascii_whitespace()
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect::<BTreeSet<_>>();
-
-        let drawed_number = right_numbers
-            .split_ascii_whitespace()
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect::<BTreeSet<_>>();
-        let matching_count = winning_numbers_hash.intersection(&drawed_number).count();
-        let copies_of_current_card = number_of_draws_per_card.get(i).unwrap();
-
-        for _ in 0..*copies_of_current_card {
-            // Create copies of following card based on the number of wins
-            for j in 0..matching_count {
-                let card_to_copy = i + j + 1;
-                number_of_draws_per_card[card_to_copy] = number_of_draws_per_card[card_to_copy] + 1
-            }
+    for (i, card) in cards.iter().enumerate() {
+        let copies_of_current_card = number_of_draws_per_card[i];
+        for copies in &mut number_of_draws_per_card[i + 1..i + 1 + card.matches()] {
+            *copies += copies_of_current_card;
         }
     }
 
@@ -67,6 +78,7 @@ fn part_two(input: &str) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2023::random::XorShift64;
 
     #[test]
     fn example_part_1() {
@@ -79,6 +91,15 @@ mod tests {
         assert_eq!(part_one(input), 13);
     }
 
+    #[test]
+    fn parse() {
+        let card = parse_card("Card  12: 41 48  3 | 83 48  6 41");
+        assert_eq!(card.id, 12);
+        assert_eq!(card.winning, BTreeSet::from([3, 41, 48]));
+        assert_eq!(card.drawn, BTreeSet::from([6, 41, 48, 83]));
+        assert_eq!(card.matches(), 2);
+    }
+
     #[test]
     fn real_part_1() {
         let input = std::fs::read_to_string("inputs/day4").unwrap();
@@ -97,6 +118,90 @@ mod tests {
         assert_eq!(part_two(input), 30);
     }
 
+    /// `part_two` as it was before, drawing the copies one at a time, to compare
+    /// the results and the timings.
+    fn part_two_per_copy(input: &str) -> u64 {
+        let cards = parse_cards(input);
+        let mut number_of_draws_per_card = vec![1; cards.len()];
+
+        for (i, card) in cards.iter().enumerate() {
+            for _ in 0..number_of_draws_per_card[i] {
+                for j in 0..card.matches() {
+                    number_of_draws_per_card[i + j + 1] += 1;
+                }
+            }
+        }
+
+        number_of_draws_per_card.iter().sum()
+    }
+
+    /// A random deck. Half of the cards win nothing and the other ones win up
+    /// to `max_matches` cards, so the number of copies grows slowly enough.
+    fn generate_deck(seed: u64, len: usize, max_matches: usize) -> String {
+        let mut rng = XorShift64::new(seed);
+
+        let mut deck = String::new();
+        for id in 1..=len {
+            let r = rng.next_u64() as usize;
+            let matches = if r.is_multiple_of(2) {
+                0
+            } else {
+                1 + r / 2 % max_matches
+            };
+            // The last cards can't win cards past the end of the deck.
+            let matches = matches.min(len - id);
+
+            let winning: Vec<String> = (1..=10).map(|n| n.to_string()).collect();
+            let drawn: Vec<String> = (1..=matches)
+                .chain(50..75 - matches)
+                .map(|n| n.to_string())
+                .collect();
+            deck.push_str(&format!(
+                "Card {id}: {} | {}\n",
+                winning.join(" "),
+                drawn.join(" ")
+            ));
+        }
+        deck
+    }
+
+    #[test]
+    fn one_step_matches_per_copy_on_generated_decks() {
+        for seed in 1..10 {
+            let deck = generate_deck(seed, 100, 3);
+            assert_eq!(part_two(&deck), part_two_per_copy(&deck), "seed {seed}");
+        }
+    }
+
+    /// Run with `cargo test --release --bin day4 -- --ignored --nocapture`.
+    ///
+    /// Drawing the copies one at a time takes as long as there are copies, so
+    /// it only gets the real input and a small deck.
+    #[test]
+    #[ignore]
+    fn bench_part_two() {
+        let real = std::fs::read_to_string("inputs/day4").unwrap();
+        let small = generate_deck(7, 1_000, 4);
+
+        for (name, input) in [("real input", &real), ("1000 cards", &small)] {
+            let start = std::time::Instant::now();
+            let expected = part_two_per_copy(input);
+            println!("per copy, {name}: {:?} ({expected} cards)", start.elapsed());
+
+            let start = std::time::Instant::now();
+            assert_eq!(part_two(input), expected);
+            println!("one step, {name}: {:?}", start.elapsed());
+        }
+
+        let large = generate_deck(7, 1_000_000, 4);
+        let start = std::time::Instant::now();
+        let total = part_two(&large);
+        println!(
+            "one step, 1000000 cards: {:?} ({total} cards)",
+            start.elapsed()
+        );
+    }
+
     #[test]
     fn real_part_2() {
         let input = std::fs::read_to_string("inputs/day4").unwrap();
@@ -0,0 +1,389 @@
+//! A rectangular grid of cells, as found in a lot of puzzles.
+//!
+//! Positions are `(row, col)` tuples, `(0, 0)` being the top-left cell.
+
+use std::ops::{Index, IndexMut};
+
+pub type Position = (usize, usize);
+
+/// Which cells count as neighbors of a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Top, left, right and bottom.
+    Four,
+    /// `Four` plus the diagonals.
+    Eight,
+    /// All the cells at a Manhattan distance of at most `k`.
+    Manhattan(usize),
+}
+
+impl Neighborhood {
+    /// Row-major offsets, the cell itself excluded.
+    fn offsets(self) -> Vec<(isize, isize)> {
+        let (radius, keep): (isize, fn(isize, isize, isize) -> bool) = match self {
+            Neighborhood::Four => (1, |d_row, d_col, _| d_row.abs() + d_col.abs() == 1),
+            Neighborhood::Eight => (1, |_, _, _| true),
+            Neighborhood::Manhattan(k) => {
+                (k as isize, |d_row, d_col, k| d_row.abs() + d_col.abs() <= k)
+            }
+        };
+
+        (-radius..=radius)
+            .flat_map(|d_row| (-radius..=radius).map(move |d_col| (d_row, d_col)))
+            .filter(|&(d_row, d_col)| (d_row, d_col) != (0, 0) && keep(d_row, d_col, radius))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adjacency {
+    pub neighborhood: Neighborhood,
+    /// Whether the edges wrap around, the grid being a torus.
+    pub wrap: bool,
+}
+
+/// The offsets of an `Adjacency` on a given grid, see `Grid::neighborhood`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Offsets {
+    deltas: Vec<(isize, isize)>,
+    wrap: bool,
+}
+
+impl Default for Adjacency {
+    /// The 8 neighbors, with hard edges.
+    fn default() -> Self {
+        Adjacency {
+            neighborhood: Neighborhood::Eight,
+            wrap: false,
+        }
+    }
+}
+
+impl std::str::FromStr for Neighborhood {
+    type Err = String;
+
+    /// `4`, `8` or `manhattan=<k>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            None if s == "4" => Ok(Neighborhood::Four),
+            None if s == "8" => Ok(Neighborhood::Eight),
+            Some(("manhattan", k)) => k
+                .parse()
+                .map(Neighborhood::Manhattan)
+                .map_err(|_| format!("Invalid radius: '{k}'")),
+            _ => Err(format!("Unknown neighborhood: '{s}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// Row after row.
+    cells: Vec<T>,
+}
+
+impl Grid<char> {
+    /// One row per non-empty line. Leading and trailing whitespace is ignored
+    /// so that inputs can be indented in tests.
+    ///
+    /// Panics if the rows don't all have the same length.
+    pub fn parse(input: &str) -> Self {
+        let rows: Vec<Vec<char>> = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect())
+            .collect();
+
+        let width = rows.first().map_or(0, Vec::len);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), width, "Row {i} does not have {width} cells");
+        }
+
+        Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for Grid<char> {
+    /// One line per row.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.height {
+            writeln!(f, "{}", self.row(row).iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): Position) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): Position) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column {col} out of bounds");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    /// All the positions, row after row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// All the cells with their position, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Top, left, right and bottom neighbors that are inside the grid.
+    pub fn neighbors4(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offsets(pos, &[(-1, 0), (0, -1), (0, 1), (1, 0)])
+    }
+
+    /// Same as `neighbors4` plus the diagonals, from top-left to bottom-right.
+    pub fn neighbors8(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offsets(
+            pos,
+            &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        )
+    }
+
+    /// What `neighbors` needs to know about `adjacency`, computed once for
+    /// this grid: offsets leading to the same cell (when wrapping around a
+    /// small grid) or back to the cell itself are removed.
+    pub fn neighborhood(&self, adjacency: Adjacency) -> Offsets {
+        let mut deltas = Vec::new();
+
+        for (d_row, d_col) in adjacency.neighborhood.offsets() {
+            let delta = if adjacency.wrap {
+                // Positive offsets, so that the neighbors are a simple modulo.
+                (
+                    d_row.rem_euclid(self.height.max(1) as isize),
+                    d_col.rem_euclid(self.width.max(1) as isize),
+                )
+            } else {
+                (d_row, d_col)
+            };
+
+            if delta != (0, 0) && !deltas.contains(&delta) {
+                deltas.push(delta);
+            }
+        }
+
+        Offsets {
+            deltas,
+            wrap: adjacency.wrap,
+        }
+    }
+
+    /// The neighbors of `pos`, see `neighborhood`.
+    pub fn neighbors<'a>(
+        &'a self,
+        (row, col): Position,
+        offsets: &'a Offsets,
+    ) -> impl Iterator<Item = Position> + 'a {
+        offsets.deltas.iter().filter_map(move |&(d_row, d_col)| {
+            if offsets.wrap {
+                Some((
+                    (row + d_row as usize) % self.height,
+                    (col + d_col as usize) % self.width,
+                ))
+            } else {
+                let row = row.checked_add_signed(d_row).filter(|&r| r < self.height)?;
+                let col = col.checked_add_signed(d_col).filter(|&c| c < self.width)?;
+                Some((row, col))
+            }
+        })
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        (row, col): Position,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + 'a {
+        offsets.iter().filter_map(move |&(d_row, d_col)| {
+            let row = row.checked_add_signed(d_row).filter(|&r| r < self.height)?;
+            let col = col.checked_add_signed(d_col).filter(|&c| c < self.width)?;
+            Some((row, col))
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("Position {pos:?} out of bounds"))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("Position {pos:?} out of bounds"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let grid = Grid::parse(
+            "abc
+             def",
+        );
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_ragged() {
+        Grid::parse("abc\nde");
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::new(3, 3, 0);
+
+        assert_eq!(
+            grid.neighbors8((1, 1)).collect::<Vec<_>>(),
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2)
+            ]
+        );
+        assert_eq!(
+            grid.neighbors8((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (1, 1)]
+        );
+        assert_eq!(
+            grid.neighbors4((2, 1)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 0), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn adjacency() {
+        let grid = Grid::new(5, 5, 0);
+        let neighbors = |pos, neighborhood, wrap| {
+            let offsets = grid.neighborhood(Adjacency { neighborhood, wrap });
+            grid.neighbors(pos, &offsets).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            neighbors((1, 1), Neighborhood::Eight, false),
+            grid.neighbors8((1, 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            neighbors((0, 0), Neighborhood::Four, false),
+            grid.neighbors4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            neighbors((0, 0), Neighborhood::Four, true),
+            vec![(4, 0), (0, 4), (0, 1), (1, 0)]
+        );
+        assert_eq!(
+            neighbors((2, 2), Neighborhood::Manhattan(2), false).len(),
+            12
+        );
+        assert_eq!(
+            neighbors((0, 0), Neighborhood::Manhattan(2), false),
+            vec![(0, 1), (0, 2), (1, 0), (1, 1), (2, 0)]
+        );
+
+        // On a 2x2 torus, everything is a neighbor of everything, once.
+        let small = Grid::new(2, 2, 0);
+        let adjacency = Adjacency {
+            neighborhood: Neighborhood::Eight,
+            wrap: true,
+        };
+        let offsets = small.neighborhood(adjacency);
+        assert_eq!(
+            small.neighbors((0, 0), &offsets).collect::<Vec<_>>(),
+            vec![(1, 1), (1, 0), (0, 1)]
+        );
+        assert_eq!(
+            small.neighbors((1, 1), &offsets).collect::<Vec<_>>(),
+            vec![(0, 0), (0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn parse_neighborhood() {
+        assert_eq!("4".parse(), Ok(Neighborhood::Four));
+        assert_eq!("manhattan=3".parse(), Ok(Neighborhood::Manhattan(3)));
+        assert!("6".parse::<Neighborhood>().is_err());
+    }
+
+    #[test]
+    fn mutate() {
+        let mut grid = Grid::new(2, 2, '.');
+        grid[(1, 1)] = '#';
+
+        assert_eq!(
+            grid.iter()
+                .filter(|(_, c)| **c == '#')
+                .map(|(pos, _)| pos)
+                .collect::<Vec<_>>(),
+            vec![(1, 1)]
+        );
+    }
+}
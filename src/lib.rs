@@ -0,0 +1,4 @@
+//! Helpers shared by several days.
+
+pub mod grid;
+pub mod random;
@@ -0,0 +1,28 @@
+//! A tiny pseudo-random generator for the generated inputs of the tests and
+//! benchmarks. Not random enough for anything else, but reproducible and free
+//! of dependencies.
+
+/// xorshift64, see <https://en.wikipedia.org/wiki/Xorshift>.
+pub struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    /// `seed` must not be 0, or the generator only returns 0.
+    pub fn new(seed: u64) -> Self {
+        assert_ne!(seed, 0, "xorshift64 needs a non-zero seed");
+        XorShift64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}